@@ -0,0 +1,182 @@
+use std::error;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use sdl2::controller::AddMappingError;
+use sdl2::IntegerOrSdlError;
+use sdl2::render::TextureValueError;
+use sdl2::video::WindowBuildError;
+use crate::lazy_foo::input::BindingsError;
+use crate::lazy_foo::script::ScriptError;
+use crate::lazy_foo::text::FontError;
+
+/// An error message reported by SDL itself (most `sdl2` calls return a plain `String`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SdlError(pub String);
+
+impl fmt::Display for SdlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SDL_Error: {}", self.0)
+    }
+}
+
+impl error::Error for SdlError {}
+
+impl From<String> for SdlError {
+    fn from(message: String) -> SdlError {
+        SdlError(message)
+    }
+}
+
+/// Everything that can go wrong while running a lesson.
+#[derive(Debug)]
+pub enum Error {
+    SdlInit(SdlError),
+    VideoSubsystem(SdlError),
+    WindowBuild(WindowBuildError),
+    CanvasBuild(IntegerOrSdlError),
+    ImageInit(SdlError),
+    EventSubsystem(SdlError),
+    EventPump(SdlError),
+    WindowSurface(SdlError),
+    AssetNotFound { path: String, searched: Vec<PathBuf> },
+    AssetLoad { path: String, source: SdlError },
+    SurfaceConvert { path: String, source: SdlError },
+    TextureCreate { path: String, source: TextureValueError },
+    ControllerSubsystem(SdlError),
+    MappingFile { path: String, source: io::Error },
+    ControllerMapping { name: String, source: AddMappingError },
+    ControllerOpen { index: u32, source: IntegerOrSdlError },
+    NoControllers,
+    JoystickSubsystem(SdlError),
+    JoystickOpen { index: u32, source: IntegerOrSdlError },
+    VirtualController(SdlError),
+    Render(SdlError),
+    Capture { path: String, source: SdlError },
+    Script { path: String, source: ScriptError },
+    EventPush(SdlError),
+    Record { path: String, source: io::Error },
+    Font { path: String, source: FontError },
+    TtfInit(SdlError),
+    FontLoad { path: String, source: SdlError },
+    TextRender { text: String, source: SdlError },
+    AudioSubsystem(SdlError),
+    MixerInit(SdlError),
+    SoundLoad { path: String, source: SdlError },
+    SoundPlay(SdlError),
+    AudioDevice(SdlError),
+    Bindings { path: String, source: BindingsError },
+    /// The lesson needs a cargo feature the binary was built without.
+    FeatureDisabled(&'static str),
+}
+
+impl Error {
+    /// Wraps a `String` error from SDL into the variant built by `variant`.
+    pub fn sdl(variant: fn(SdlError) -> Error) -> impl Fn(String) -> Error {
+        move |message| variant(SdlError(message))
+    }
+
+    pub fn asset_load(path: &str) -> impl Fn(String) -> Error + '_ {
+        move |message| Error::AssetLoad { path: path.to_string(), source: SdlError(message) }
+    }
+
+    pub fn surface_convert(path: &str) -> impl Fn(String) -> Error + '_ {
+        move |message| Error::SurfaceConvert { path: path.to_string(), source: SdlError(message) }
+    }
+
+    pub fn capture(path: &Path, message: String) -> Error {
+        Error::Capture { path: path.display().to_string(), source: SdlError(message) }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::SdlInit(_) => write!(f, "SDL could not initialize!"),
+            Error::VideoSubsystem(_) => write!(f, "SDL video subsystem could not initialize!"),
+            Error::WindowBuild(_) => write!(f, "Window could not be created!"),
+            Error::CanvasBuild(_) => write!(f, "Renderer could not be created!"),
+            Error::ImageInit(_) => write!(f, "SDL_image could not initialize!"),
+            Error::EventSubsystem(_) => write!(f, "SDL event subsystem could not initialize!"),
+            Error::EventPump(_) => write!(f, "Event pump could not be created!"),
+            Error::WindowSurface(_) => write!(f, "Window surface could not be retrieved!"),
+            Error::AssetNotFound { path, searched } => {
+                write!(f, "Unable to find {}! Searched in:", path)?;
+                for directory in searched {
+                    write!(f, " {}", directory.display())?;
+                }
+                Ok(())
+            },
+            Error::AssetLoad { path, .. } => write!(f, "Unable to load image {}!", path),
+            Error::SurfaceConvert { path, .. } => write!(f, "Unable to optimize image {}!", path),
+            Error::TextureCreate { path, .. } => write!(f, "Unable to create texture from {}!", path),
+            Error::ControllerSubsystem(_) => write!(f, "SDL game controller subsystem could not initialize!"),
+            Error::MappingFile { path, .. } => write!(f, "Unable to access controller mappings in {}!", path),
+            Error::ControllerMapping { name, .. } => write!(f, "Unable to add the controller mapping for {}!", name),
+            Error::ControllerOpen { index, .. } => write!(f, "Unable to open game controller {}!", index),
+            Error::NoControllers => write!(f, "No joysticks connected!"),
+            Error::JoystickSubsystem(_) => write!(f, "SDL joystick subsystem could not initialize!"),
+            Error::JoystickOpen { index, .. } => write!(f, "Unable to open joystick {}!", index),
+            Error::VirtualController(_) => write!(f, "Unable to attach virtual controller!"),
+            Error::Render(_) => write!(f, "Rendering failed!"),
+            Error::Capture { path, .. } => write!(f, "Unable to capture frame to {}!", path),
+            Error::Script { path, .. } => write!(f, "Unable to load event script {}!", path),
+            Error::EventPush(_) => write!(f, "Unable to push event into the queue!"),
+            Error::Record { path, .. } => write!(f, "Unable to record events to {}!", path),
+            Error::Font { path, .. } => write!(f, "Unable to load font {}!", path),
+            Error::TtfInit(_) => write!(f, "SDL_ttf could not initialize!"),
+            Error::FontLoad { path, .. } => write!(f, "Failed to load font {}!", path),
+            Error::TextRender { text, .. } => write!(f, "Unable to render text surface for '{}'!", text),
+            Error::AudioSubsystem(_) => write!(f, "SDL audio subsystem could not initialize!"),
+            Error::MixerInit(_) => write!(f, "SDL_mixer could not initialize!"),
+            Error::SoundLoad { path, .. } => write!(f, "Failed to load sound {}!", path),
+            Error::SoundPlay(_) => write!(f, "Unable to play sound!"),
+            Error::AudioDevice(_) => write!(f, "Failed to open audio device!"),
+            Error::Bindings { path, .. } => write!(f, "Unable to load bindings {}!", path),
+            Error::FeatureDisabled(feature) => {
+                write!(f, "This lesson needs the {} feature! Rebuild with `--features {}`.", feature, feature)
+            },
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::SdlInit(source)
+            | Error::VideoSubsystem(source)
+            | Error::ImageInit(source)
+            | Error::EventSubsystem(source)
+            | Error::EventPump(source)
+            | Error::WindowSurface(source)
+            | Error::AssetLoad { source, .. }
+            | Error::SurfaceConvert { source, .. }
+            | Error::ControllerSubsystem(source)
+            | Error::Render(source)
+            | Error::Capture { source, .. }
+            | Error::EventPush(source)
+            | Error::TtfInit(source)
+            | Error::FontLoad { source, .. }
+            | Error::TextRender { source, .. }
+            | Error::AudioSubsystem(source)
+            | Error::MixerInit(source)
+            | Error::SoundLoad { source, .. }
+            | Error::SoundPlay(source)
+            | Error::AudioDevice(source)
+            | Error::JoystickSubsystem(source)
+            | Error::VirtualController(source) => Some(source),
+            Error::WindowBuild(source) => Some(source),
+            Error::CanvasBuild(source) => Some(source),
+            Error::TextureCreate { source, .. } => Some(source),
+            Error::MappingFile { source, .. } => Some(source),
+            Error::ControllerMapping { source, .. } => Some(source),
+            Error::ControllerOpen { source, .. } => Some(source),
+            Error::JoystickOpen { source, .. } => Some(source),
+            Error::Script { source, .. } => Some(source),
+            Error::Bindings { source, .. } => Some(source),
+            Error::Record { source, .. } => Some(source),
+            Error::Font { source, .. } => Some(source),
+            Error::AssetNotFound { .. } | Error::NoControllers | Error::FeatureDisabled(_) => None,
+        }
+    }
+}
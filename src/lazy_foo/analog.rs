@@ -0,0 +1,205 @@
+use std::collections::HashMap;
+use sdl2::controller::Axis;
+use crate::lazy_foo::controllers::Player;
+
+/// Turns a raw axis value, -32768 to 32767, into -1.0 to 1.0.
+pub fn normalize(value: i16) -> f32 {
+    (value as f32 / 32767.0).clamp(-1.0, 1.0)
+}
+
+/// How far the stick has to be pushed, from 0.0 to 1.0 past the dead zone, for how much
+/// of the output it gives.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResponseCurve {
+    Linear,
+    /// `input ^ exponent`: above 1.0 gives finer control near the center.
+    Exponential(f32),
+    /// Straight lines between `(input, output)` points, sorted by input, starting from
+    /// `(0, 0)` and ending at `(1, 1)`.
+    Custom(Vec<(f32, f32)>),
+}
+
+impl ResponseCurve {
+    pub fn apply(&self, input: f32) -> f32 {
+        let input = input.clamp(0.0, 1.0);
+
+        match self {
+            ResponseCurve::Linear => input,
+            ResponseCurve::Exponential(exponent) => input.powf(*exponent),
+            ResponseCurve::Custom(points) => {
+                let mut previous = (0.0, 0.0);
+                for &(x, y) in points.iter().chain([(1.0, 1.0)].iter()) {
+                    if input <= x {
+                        let span = x - previous.0;
+                        if span <= 0.0 {
+                            return y;
+                        }
+                        return previous.1 + (y - previous.1) * (input - previous.0) / span;
+                    }
+                    previous = (x, y);
+                }
+                1.0
+            }
+        }
+    }
+}
+
+/// Rescales `magnitude` so `inner` reads 0.0 and `outer` reads 1.0.
+fn rescale(magnitude: f32, inner: f32, outer: f32) -> f32 {
+    if magnitude <= inner {
+        return 0.0;
+    }
+    if outer <= inner {
+        return 1.0;
+    }
+    ((magnitude - inner) / (outer - inner)).min(1.0)
+}
+
+/// How a stick's two axes are turned into the position a lesson uses.
+///
+/// Dead zones are fractions of the stick's full travel. The axial dead zone is applied to
+/// each axis on its own, so pushing straight up does not drift sideways; the radial one to
+/// the distance from the center, so a worn stick resting off center reads as centered
+/// whichever way it leans. Anything past the outer dead zone reads as fully pushed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StickConfig {
+    pub radial_dead_zone: f32,
+    pub axial_dead_zone: f32,
+    pub outer_dead_zone: f32,
+    pub curve: ResponseCurve,
+}
+
+impl Default for StickConfig {
+    fn default() -> StickConfig {
+        StickConfig { radial_dead_zone: 0.15, axial_dead_zone: 0.0, outer_dead_zone: 0.95, curve: ResponseCurve::Linear }
+    }
+}
+
+impl StickConfig {
+    /// The stick's position for raw axis values `x` and `y`, inside the unit circle.
+    pub fn process(&self, x: i16, y: i16) -> (f32, f32) {
+        let axial = |value: f32| value.signum() * rescale(value.abs(), self.axial_dead_zone, 1.0);
+        let (x, y) = (axial(normalize(x)), axial(normalize(y)));
+
+        let magnitude = (x * x + y * y).sqrt();
+        let distance = rescale(magnitude, self.radial_dead_zone, self.outer_dead_zone);
+        if distance == 0.0 {
+            return (0.0, 0.0);
+        }
+
+        let output = self.curve.apply(distance);
+        (x / magnitude * output, y / magnitude * output)
+    }
+}
+
+/// How a trigger's axis is turned into an amount and into a digital press.
+///
+/// The press needs the processed amount to reach `press_threshold` and lasts until it
+/// falls below `release_threshold`, so a trigger held right at the threshold does not
+/// flicker between pressed and released.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TriggerConfig {
+    pub dead_zone: f32,
+    pub outer_dead_zone: f32,
+    pub curve: ResponseCurve,
+    pub press_threshold: f32,
+    pub release_threshold: f32,
+}
+
+impl Default for TriggerConfig {
+    fn default() -> TriggerConfig {
+        TriggerConfig {
+            dead_zone: 0.05,
+            outer_dead_zone: 0.95,
+            curve: ResponseCurve::Linear,
+            press_threshold: 0.5,
+            release_threshold: 0.4,
+        }
+    }
+}
+
+impl TriggerConfig {
+    /// How far the trigger is pulled for raw axis `value`, from 0.0 to 1.0.
+    pub fn process(&self, value: i16) -> f32 {
+        let pulled = normalize(value).max(0.0);
+        self.curve.apply(rescale(pulled, self.dead_zone, self.outer_dead_zone))
+    }
+
+    /// Whether the trigger counts as pressed at `value`, given whether it was before.
+    pub fn pressed(&self, value: i16, was_pressed: bool) -> bool {
+        let amount = self.process(value);
+        if was_pressed {
+            amount >= self.release_threshold
+        } else {
+            amount >= self.press_threshold
+        }
+    }
+}
+
+/// The stick and trigger settings of one controller.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AxisConfig {
+    pub left_stick: StickConfig,
+    pub right_stick: StickConfig,
+    pub left_trigger: TriggerConfig,
+    pub right_trigger: TriggerConfig,
+}
+
+/// What a controller's sticks and triggers read after processing.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ProcessedAxes {
+    pub left_stick: (f32, f32),
+    pub right_stick: (f32, f32),
+    pub left_trigger: f32,
+    pub right_trigger: f32,
+    pub left_trigger_pressed: bool,
+    pub right_trigger_pressed: bool,
+}
+
+/// Processes every player's axes with the settings of their controller, keyed by GUID, or
+/// the default ones for controllers without settings of their own.
+///
+/// Trigger presses are remembered per controller between calls to [`AxisProcessor::process`].
+#[derive(Debug, Clone, Default)]
+pub struct AxisProcessor {
+    default: AxisConfig,
+    configs: HashMap<String, AxisConfig>,
+    triggers: HashMap<u32, (bool, bool)>,
+}
+
+impl AxisProcessor {
+    pub fn new(default: AxisConfig) -> AxisProcessor {
+        AxisProcessor { default, ..AxisProcessor::default() }
+    }
+
+    /// Uses `config` for every controller with `guid`.
+    pub fn configure(&mut self, guid: &str, config: AxisConfig) {
+        self.configs.insert(guid.to_string(), config);
+    }
+
+    pub fn config(&self, guid: &str) -> &AxisConfig {
+        self.configs.get(guid).unwrap_or(&self.default)
+    }
+
+    /// The settings of controllers with `guid`, starting from a copy of the default ones.
+    pub fn config_mut(&mut self, guid: &str) -> &mut AxisConfig {
+        self.configs.entry(guid.to_string()).or_insert_with(|| self.default.clone())
+    }
+
+    pub fn process(&mut self, player: &Player) -> ProcessedAxes {
+        let config = self.configs.get(player.guid()).unwrap_or(&self.default);
+        let (left_was, right_was) = self.triggers.get(&player.instance_id()).copied().unwrap_or_default();
+
+        let processed = ProcessedAxes {
+            left_stick: config.left_stick.process(player.axis(Axis::LeftX), player.axis(Axis::LeftY)),
+            right_stick: config.right_stick.process(player.axis(Axis::RightX), player.axis(Axis::RightY)),
+            left_trigger: config.left_trigger.process(player.axis(Axis::TriggerLeft)),
+            right_trigger: config.right_trigger.process(player.axis(Axis::TriggerRight)),
+            left_trigger_pressed: config.left_trigger.pressed(player.axis(Axis::TriggerLeft), left_was),
+            right_trigger_pressed: config.right_trigger.pressed(player.axis(Axis::TriggerRight), right_was),
+        };
+
+        self.triggers.insert(player.instance_id(), (processed.left_trigger_pressed, processed.right_trigger_pressed));
+        processed
+    }
+}
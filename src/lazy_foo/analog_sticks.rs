@@ -0,0 +1,236 @@
+use std::time::Duration;
+use sdl2::controller::Axis;
+use sdl2::event::Event;
+use sdl2::keyboard::Keycode;
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::WindowCanvas;
+use crate::Error;
+use crate::lazy_foo::analog::{normalize, AxisProcessor, ProcessedAxes, ResponseCurve, StickConfig};
+use crate::lazy_foo::app::{App, Options, SCREEN_HEIGHT, SCREEN_WIDTH};
+use crate::lazy_foo::controllers::Controllers;
+use crate::lazy_foo::draw::{draw_circle, fill_circle};
+use crate::lazy_foo::scene::{self, Scene, Textures, Transition};
+use crate::lazy_foo::text::{Align, Font, TextStyle};
+
+const HELP: &str = "1-3: curve  Up/Down: radial dead zone  Left/Right: axial dead zone";
+
+/// Pixels per second the sprite moves with the stick fully pushed, doubled with the left
+/// trigger pressed.
+const SPEED: f32 = 320.0;
+const SPRITE_SIZE: u32 = 24;
+const DEAD_ZONE_STEP: f32 = 0.05;
+
+/// The stick preview in the corner: center and radius of the full stick travel.
+const PREVIEW: (i32, i32) = (72, 392);
+const PREVIEW_RADIUS: i32 = 56;
+
+const BACKGROUND: Color = Color::RGB(0x10, 0x10, 0x30);
+const SPRITE: Color = Color::RGB(0x40, 0xC0, 0xFF);
+const PROCESSED: Color = Color::YELLOW;
+const RAW: Color = Color::RGB(0x80, 0x80, 0x80);
+const DEAD_ZONE: Color = Color::RGB(0xC0, 0x40, 0x40);
+const TEXT: Color = Color::WHITE;
+
+fn curve_name(curve: &ResponseCurve) -> &'static str {
+    match curve {
+        ResponseCurve::Linear => "linear",
+        ResponseCurve::Exponential(_) => "exponential",
+        ResponseCurve::Custom(_) => "custom",
+    }
+}
+
+/// Moves a sprite with the first controller's left stick, processed through its dead
+/// zones and response curve, which the keyboard changes for every controller with the
+/// same GUID.
+///
+/// Pulling the left trigger past its threshold doubles the speed, and pulling the right
+/// one brings the sprite back to the center.
+pub struct AnalogSticks {
+    controllers: Controllers,
+    processor: AxisProcessor,
+    position: (f32, f32),
+    /// Where the sprite was before the last update, and how far the frame is from there
+    /// to `position`, so the sprite moves smoothly whatever the frame rate.
+    previous: (f32, f32),
+    alpha: f32,
+    fps: f64,
+    /// Left stick as the controller reports it, and what processing made of it.
+    raw: (i16, i16),
+    processed: ProcessedAxes,
+    /// Settings of the controller being shown, to draw them.
+    config: StickConfig,
+    font: Option<Font>,
+}
+
+impl AnalogSticks {
+    pub fn boxed() -> Box<dyn Scene> {
+        Box::new(AnalogSticks {
+            controllers: Controllers::new(),
+            processor: AxisProcessor::default(),
+            position: AnalogSticks::center(),
+            previous: AnalogSticks::center(),
+            alpha: 0.0,
+            fps: 0.0,
+            raw: (0, 0),
+            processed: ProcessedAxes::default(),
+            config: StickConfig::default(),
+            font: None,
+        })
+    }
+
+    fn center() -> (f32, f32) {
+        ((SCREEN_WIDTH - SPRITE_SIZE) as f32 / 2.0, (SCREEN_HEIGHT - SPRITE_SIZE) as f32 / 2.0)
+    }
+
+    /// Changes the left stick settings of the first controller's GUID.
+    fn configure(&mut self, change: impl FnOnce(&mut StickConfig)) {
+        if let Some(player) = self.controllers.players().next() {
+            change(&mut self.processor.config_mut(player.guid()).left_stick);
+        }
+    }
+}
+
+impl Scene for AnalogSticks {
+    fn on_enter(&mut self, app: &mut App) -> Result<(), Error> {
+        self.font = Some(Font::builtin()?);
+        self.controllers.open_all(app)
+    }
+
+    fn on_exit(&mut self, _app: &mut App) -> Result<(), Error> {
+        self.controllers.close_all();
+        Ok(())
+    }
+
+    fn on_event(&mut self, app: &mut App, event: &Event) -> Result<Transition, Error> {
+        self.controllers.handle_event(app, event)?;
+
+        if let Event::KeyDown { keycode: Some(keycode), .. } = *event {
+            match keycode {
+                Keycode::Num1 => self.configure(|config| config.curve = ResponseCurve::Linear),
+                Keycode::Num2 => self.configure(|config| config.curve = ResponseCurve::Exponential(2.0)),
+                Keycode::Num3 => self.configure(|config| config.curve = ResponseCurve::Custom(vec![(0.6, 0.2), (0.9, 0.5)])),
+                Keycode::Up => self.configure(|config| {
+                    config.radial_dead_zone = (config.radial_dead_zone + DEAD_ZONE_STEP).min(0.9);
+                }),
+                Keycode::Down => self.configure(|config| {
+                    config.radial_dead_zone = (config.radial_dead_zone - DEAD_ZONE_STEP).max(0.0);
+                }),
+                Keycode::Right => self.configure(|config| {
+                    config.axial_dead_zone = (config.axial_dead_zone + DEAD_ZONE_STEP).min(0.9);
+                }),
+                Keycode::Left => self.configure(|config| {
+                    config.axial_dead_zone = (config.axial_dead_zone - DEAD_ZONE_STEP).max(0.0);
+                }),
+                _ => ()
+            }
+        }
+
+        Ok(Transition::Stay)
+    }
+
+    fn update(&mut self, _app: &mut App, dt: Duration) -> Result<Transition, Error> {
+        self.previous = self.position;
+        let player = match self.controllers.players().next() {
+            None => return Ok(Transition::Stay),
+            Some(player) => player
+        };
+
+        let was_recentering = self.processed.right_trigger_pressed;
+        self.processed = self.processor.process(player);
+        self.raw = (player.axis(Axis::LeftX), player.axis(Axis::LeftY));
+        self.config = self.processor.config(player.guid()).left_stick.clone();
+
+        if self.processed.right_trigger_pressed && !was_recentering {
+            self.position = AnalogSticks::center();
+            self.previous = self.position;
+        }
+
+        let speed = if self.processed.left_trigger_pressed { SPEED * 2.0 } else { SPEED };
+        let (x, y) = self.processed.left_stick;
+        let seconds = dt.as_secs_f32();
+        self.position = (
+            (self.position.0 + x * speed * seconds).clamp(0.0, (SCREEN_WIDTH - SPRITE_SIZE) as f32),
+            (self.position.1 + y * speed * seconds).clamp(0.0, (SCREEN_HEIGHT - SPRITE_SIZE) as f32),
+        );
+
+        Ok(Transition::Stay)
+    }
+
+    fn before_render(&mut self, app: &App) {
+        self.alpha = app.alpha();
+        self.fps = app.stats().fps();
+    }
+
+    fn render(&mut self, canvas: &mut WindowCanvas, textures: &mut Textures) -> Result<(), Error> {
+        canvas.set_draw_color(BACKGROUND);
+        canvas.clear();
+
+        canvas.set_draw_color(SPRITE);
+        let drawn = (
+            self.previous.0 + (self.position.0 - self.previous.0) * self.alpha,
+            self.previous.1 + (self.position.1 - self.previous.1) * self.alpha,
+        );
+        let sprite = Rect::new(drawn.0.round() as i32, drawn.1.round() as i32, SPRITE_SIZE, SPRITE_SIZE);
+        canvas.fill_rect(sprite).map_err(Error::sdl(Error::Render))?;
+
+        // The stick's travel, its dead zones, and where the raw and processed stick are.
+        let (x, y) = PREVIEW;
+        let radius = PREVIEW_RADIUS as f32;
+        canvas.set_draw_color(RAW);
+        draw_circle(canvas, x, y, PREVIEW_RADIUS)?;
+        canvas.set_draw_color(DEAD_ZONE);
+        draw_circle(canvas, x, y, (self.config.radial_dead_zone * radius) as i32)?;
+        draw_circle(canvas, x, y, (self.config.outer_dead_zone * radius) as i32)?;
+        let axial = (self.config.axial_dead_zone * radius) as i32;
+        if axial > 0 {
+            let size = PREVIEW_RADIUS as u32 * 2;
+            canvas.draw_rect(Rect::new(x - axial, y - PREVIEW_RADIUS, axial as u32 * 2, size))
+                .and_then(|_| canvas.draw_rect(Rect::new(x - PREVIEW_RADIUS, y - axial, size, axial as u32 * 2)))
+                .map_err(Error::sdl(Error::Render))?;
+        }
+
+        canvas.set_draw_color(RAW);
+        let raw = (normalize(self.raw.0) * radius, normalize(self.raw.1) * radius);
+        fill_circle(canvas, x + raw.0 as i32, y + raw.1 as i32, 3)?;
+        canvas.set_draw_color(PROCESSED);
+        let (px, py) = self.processed.left_stick;
+        fill_circle(canvas, x + (px * radius) as i32, y + (py * radius) as i32, 4)?;
+
+        let font = match &self.font {
+            None => return Ok(()),
+            Some(font) => font
+        };
+        let mut font = textures.font(font)?;
+        let center = SCREEN_WIDTH as i32 / 2;
+        font.draw(canvas, HELP, center, 8, &TextStyle::new(TEXT).align(Align::Center))?;
+
+        if self.controllers.is_empty() {
+            let style = TextStyle::new(PROCESSED).scale(2).align(Align::Center);
+            font.draw(canvas, "Connect a controller", center, 96, &style)?;
+        }
+
+        let lines = [
+            format!("Curve: {}", curve_name(&self.config.curve)),
+            format!("Radial dead zone: {:.2}", self.config.radial_dead_zone),
+            format!("Axial dead zone: {:.2}", self.config.axial_dead_zone),
+            format!("Outer dead zone: {:.2}", self.config.outer_dead_zone),
+            format!("Stick: {:+.2} {:+.2}", px, py),
+            format!(
+                "LT {:.2}{}  RT {:.2}{}",
+                self.processed.left_trigger, if self.processed.left_trigger_pressed { " boost" } else { "" },
+                self.processed.right_trigger, if self.processed.right_trigger_pressed { " center" } else { "" },
+            ),
+            format!("FPS: {:.0}", self.fps),
+        ];
+        for (line, text) in lines.iter().enumerate() {
+            font.draw(canvas, text, 144, 344 + line as i32 * 14, &TextStyle::new(TEXT))?;
+        }
+
+        Ok(())
+    }
+}
+
+pub fn run(options: &Options) -> Result<(), Error> {
+    scene::run(options, vec![AnalogSticks::boxed()])
+}
@@ -0,0 +1,695 @@
+use std::env;
+use std::path::{Path, PathBuf};
+#[cfg(feature = "ttf")]
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+use sdl2::{AudioSubsystem, EventPump, EventSubsystem, GameControllerSubsystem, JoystickSubsystem, Sdl, VideoSubsystem};
+use sdl2::event::Event;
+use sdl2::hint;
+use sdl2::image::{InitFlag, Sdl2ImageContext};
+use sdl2::keyboard::Keycode;
+use sdl2::render::{CanvasBuilder, TextureCreator, WindowCanvas};
+use sdl2::video::{Window, WindowContext, WindowSurfaceRef};
+use crate::Error;
+use crate::lazy_foo::assets::Assets;
+use crate::lazy_foo::capture::{Capture, Image};
+use crate::lazy_foo::input::{self, ActionMap};
+use crate::lazy_foo::main_loop::{FrameStats, LoopConfig, MainLoop};
+use crate::lazy_foo::mapping::{self, Mapping, MappingDb, Platform};
+use crate::lazy_foo::script::{Recorder, Script, ScriptPlayer};
+#[cfg(feature = "ttf")]
+use crate::lazy_foo::ttf::TrueType;
+use crate::lazy_foo::virtual_controller::VirtualController;
+
+pub const SCREEN_WIDTH: u32 = 640;
+pub const SCREEN_HEIGHT: u32 = 480;
+
+/// Asset the game controller mappings are loaded from.
+pub const CONTROLLER_MAPPINGS: &str = "controller_mapping.txt";
+
+/// How often asset files are checked for changes.
+const RELOAD_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How the launcher (or a test) wants a lesson to run.
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    /// Use SDL's dummy video driver and a software renderer, so no display or GPU is needed.
+    pub headless: bool,
+    /// Number of frames after which the lesson returns on its own. `None` runs until quit.
+    pub frame_budget: Option<u32>,
+    /// Frame to save to disk when it is presented.
+    pub capture: Option<Capture>,
+    /// Events to push into the lesson's queue in place of real input.
+    pub script: Option<Script>,
+    /// File to record every received event to, as a replayable script.
+    pub record: Option<PathBuf>,
+    /// Directory searched first for images and other assets.
+    pub asset_root: Option<PathBuf>,
+    /// File SDL's disk audio driver writes everything the lesson plays to, as raw samples.
+    pub audio_output: Option<PathBuf>,
+    /// Bindings file whose actions replace the standard ones of the same name, and where
+    /// rebound actions are saved. Defaults to [`input::user_bindings_path`] unless headless.
+    pub bindings: Option<PathBuf>,
+    /// Number of [`VirtualController`]s to plug in before the lesson starts. Scripted
+    /// controller events for controller `n` below this number drive the `n`th of them.
+    pub virtual_controllers: u32,
+    /// GameControllerDB file the mapping wizard appends to, merged over [`CONTROLLER_MAPPINGS`].
+    /// Defaults to [`mapping::user_mappings_path`] unless headless.
+    pub user_mappings: Option<PathBuf>,
+    /// GameControllerDB files merged over [`Options::user_mappings`], later ones winning.
+    /// `$SDL_GAMECONTROLLERCONFIG` is merged last, over all of them.
+    pub mappings: Vec<PathBuf>,
+    /// Frames an earlier window of the same run already went through, like the menu's
+    /// before it started a lesson in a window of its own. Frames are counted on from
+    /// there, scripted events of those frames are not played again and the recording
+    /// carries on in the same file.
+    pub frames_before: u32,
+}
+
+impl Options {
+    /// Headless options that stop the lesson after `frames` frames.
+    pub fn headless(frames: u32) -> Options {
+        Options { headless: true, frame_budget: Some(frames), ..Options::default() }
+    }
+}
+
+#[derive(Default)]
+struct RendererOptions {
+    accelerated: bool,
+    software: bool,
+    vsync: bool,
+}
+
+/// Collects everything a lesson needs from SDL before it can start drawing.
+///
+/// By default the app draws through the window surface. Calling [`AppBuilder::renderer`]
+/// (or any of the renderer flags) switches it to a hardware/software `WindowCanvas`.
+pub struct AppBuilder {
+    title: String,
+    width: u32,
+    height: u32,
+    renderer: Option<RendererOptions>,
+    image_formats: Option<InitFlag>,
+    hints: Vec<(String, String)>,
+    loop_config: LoopConfig,
+    escape_quits: bool,
+    options: Options,
+}
+
+impl AppBuilder {
+    pub fn new(title: &str) -> AppBuilder {
+        AppBuilder {
+            title: title.to_string(),
+            width: SCREEN_WIDTH,
+            height: SCREEN_HEIGHT,
+            renderer: None,
+            image_formats: None,
+            hints: Vec::new(),
+            loop_config: LoopConfig::default(),
+            escape_quits: false,
+            options: Options::default(),
+        }
+    }
+
+    pub fn size(mut self, width: u32, height: u32) -> AppBuilder {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    /// Draws through a `WindowCanvas` instead of the window surface.
+    pub fn renderer(mut self) -> AppBuilder {
+        self.renderer.get_or_insert_with(RendererOptions::default);
+        self
+    }
+
+    pub fn accelerated(mut self) -> AppBuilder {
+        self.renderer.get_or_insert_with(RendererOptions::default).accelerated = true;
+        self
+    }
+
+    pub fn software(mut self) -> AppBuilder {
+        self.renderer.get_or_insert_with(RendererOptions::default).software = true;
+        self
+    }
+
+    /// Paces frames with the display's refresh instead of the frame cap.
+    pub fn vsync(mut self) -> AppBuilder {
+        self.renderer.get_or_insert_with(RendererOptions::default).vsync = true;
+        self.loop_config.frame_cap = None;
+        self
+    }
+
+    /// Number of fixed updates per second (60 by default).
+    pub fn tick_rate(mut self, ticks_per_second: u32) -> AppBuilder {
+        self.loop_config.tick_rate = ticks_per_second;
+        self
+    }
+
+    /// Caps how many frames are presented per second (60 by default), or uncaps it with `None`.
+    pub fn frame_cap(mut self, frames_per_second: Option<u32>) -> AppBuilder {
+        self.loop_config.frame_cap = frames_per_second;
+        self
+    }
+
+    /// Initializes SDL_image for the given formats.
+    pub fn image(mut self, formats: InitFlag) -> AppBuilder {
+        self.image_formats = Some(self.image_formats.unwrap_or_else(InitFlag::empty) | formats);
+        self
+    }
+
+    /// Sets an SDL hint (e.g. `SDL_RENDER_SCALE_QUALITY`) before the window is created.
+    pub fn hint(mut self, name: &str, value: &str) -> AppBuilder {
+        self.hints.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Turns Escape into a quit event, for lessons that only stop when their window is
+    /// closed but are started from somewhere Escape goes back to.
+    pub fn escape_quits(mut self) -> AppBuilder {
+        self.escape_quits = true;
+        self
+    }
+
+    /// Applies the launcher's run options (headless mode, frame budget).
+    pub fn options(mut self, options: &Options) -> AppBuilder {
+        self.options = options.clone();
+        self
+    }
+
+    pub fn build(mut self) -> Result<App, Error> {
+        if let Some(path) = &self.options.audio_output {
+            env::set_var("SDL_AUDIODRIVER", "disk");
+            env::set_var("SDL_DISKAUDIOFILE", path);
+            // Unset, the disk driver waits one buffer's length per buffer like a sound card
+            // would, so the file holds as many samples as the lesson ran for.
+            env::remove_var("SDL_DISKAUDIODELAY");
+        } else if self.options.headless {
+            env::set_var("SDL_AUDIODRIVER", "dummy");
+        }
+
+        if self.options.headless {
+            env::set_var("SDL_VIDEODRIVER", "dummy");
+            self.hints.push(("SDL_RENDER_DRIVER".to_string(), "software".to_string()));
+            if let Some(renderer) = &mut self.renderer {
+                *renderer = RendererOptions { software: true, ..RendererOptions::default() };
+            }
+        }
+
+        let sdl = sdl2::init().map_err(Error::sdl(Error::SdlInit))?;
+
+        for (name, value) in &self.hints {
+            if !hint::set(name, value) {
+                println!("Warning: hint {} could not be set to {}!", name, value);
+            }
+        }
+
+        let video = sdl.video().map_err(Error::sdl(Error::VideoSubsystem))?;
+
+        let window = video
+            .window(&self.title, self.width, self.height)
+            .build()
+            .map_err(Error::WindowBuild)?;
+
+        let target = match self.renderer {
+            None => Target::Surface(window),
+            Some(options) => {
+                let mut builder = CanvasBuilder::new(window);
+                if options.accelerated {
+                    builder = builder.accelerated();
+                }
+                if options.software {
+                    builder = builder.software();
+                }
+                if options.vsync {
+                    builder = builder.present_vsync();
+                }
+                Target::Renderer(builder.build().map_err(Error::CanvasBuild)?)
+            }
+        };
+
+        let image = match self.image_formats {
+            None => None,
+            Some(formats) => {
+                Some(sdl2::image::init(formats).map_err(Error::sdl(Error::ImageInit))?)
+            }
+        };
+
+        let recorder = match &self.options.record {
+            None => None,
+            Some(path) if self.options.frames_before > 0 => Some(Recorder::append(path)?),
+            Some(path) => Some(Recorder::create(path)?)
+        };
+        let player = self.options.script.map(|script| {
+            let mut player = ScriptPlayer::new(script);
+            player.skip(self.options.frames_before);
+            player
+        });
+
+        // Headless runs leave the user's own bindings alone, so they behave the same everywhere.
+        let bindings_path = match self.options.bindings {
+            Some(path) => Some(path),
+            None if self.options.headless => None,
+            None => input::user_bindings_path()
+        };
+        let user_mappings_path = match self.options.user_mappings {
+            Some(path) => Some(path),
+            None if self.options.headless => None,
+            None => mapping::user_mappings_path()
+        };
+        let mut actions = ActionMap::standard();
+        if let Some(path) = bindings_path.as_deref().filter(|path| path.is_file()) {
+            // A broken bindings file should not keep every lesson from starting.
+            match ActionMap::load(path) {
+                Ok(bindings) => actions.extend(bindings),
+                Err(Error::Bindings { path, source }) => {
+                    println!("Warning: Unable to load bindings {}, keeping the defaults! {}", path, source);
+                },
+                Err(error) => println!("Warning: {}", error)
+            }
+        }
+
+        let event = sdl.event().map_err(Error::sdl(Error::EventSubsystem))?;
+        let event_pump = sdl.event_pump().map_err(Error::sdl(Error::EventPump))?;
+
+        let mut app = App {
+            target,
+            event_pump,
+            event,
+            virtual_controllers: Vec::new(),
+            game_controller: None,
+            joystick: None,
+            audio: None,
+            video,
+            _image: image,
+            #[cfg(feature = "ttf")]
+            ttf: None,
+            sdl,
+            frame_budget: self.options.frame_budget,
+            frames: self.options.frames_before,
+            capture: self.options.capture,
+            player,
+            recorder,
+            assets: Assets::new(self.options.asset_root.as_deref()),
+            actions,
+            bindings_path,
+            user_mappings_path,
+            mapping_files: self.options.mappings,
+            mappings: None,
+            reloaded: Vec::new(),
+            last_reload_check: Instant::now(),
+            main_loop: MainLoop::new(self.loop_config, self.options.headless),
+            escape_quits: self.escape_quits,
+        };
+
+        for _ in 0..self.options.virtual_controllers {
+            app.attach_virtual_controller()?;
+        }
+
+        Ok(app)
+    }
+}
+
+enum Target {
+    Surface(Window),
+    Renderer(WindowCanvas),
+}
+
+/// Owns every SDL context a lesson uses.
+///
+/// Fields are dropped in declaration order, so the window is destroyed before the
+/// subsystems and the SDL context it was created from.
+pub struct App {
+    target: Target,
+    event_pump: EventPump,
+    event: EventSubsystem,
+    virtual_controllers: Vec<VirtualController>,
+    game_controller: Option<GameControllerSubsystem>,
+    joystick: Option<JoystickSubsystem>,
+    audio: Option<AudioSubsystem>,
+    video: VideoSubsystem,
+    _image: Option<Sdl2ImageContext>,
+    #[cfg(feature = "ttf")]
+    ttf: Option<Rc<TrueType>>,
+    sdl: Sdl,
+    frame_budget: Option<u32>,
+    frames: u32,
+    capture: Option<Capture>,
+    player: Option<ScriptPlayer>,
+    recorder: Option<Recorder>,
+    assets: Assets,
+    actions: ActionMap,
+    bindings_path: Option<PathBuf>,
+    user_mappings_path: Option<PathBuf>,
+    mapping_files: Vec<PathBuf>,
+    mappings: Option<MappingDb>,
+    reloaded: Vec<String>,
+    last_reload_check: Instant,
+    main_loop: MainLoop,
+    escape_quits: bool,
+}
+
+impl App {
+    /// Starts a new frame, returning `false` once the frame budget has been used up.
+    ///
+    /// Scripted events due on the new frame are pushed here, so the lesson sees them
+    /// when it next polls the event pump, and modified asset files are reloaded.
+    /// [`App::ticks`] and [`App::alpha`] are worked out from the time since the last frame.
+    pub fn next_frame(&mut self) -> Result<bool, Error> {
+        if let Some(budget) = self.frame_budget {
+            if self.frames >= budget {
+                return Ok(false);
+            }
+        }
+
+        self.frames += 1;
+        self.main_loop.begin_frame();
+        self.actions.begin_frame();
+
+        self.reloaded.clear();
+        if self.last_reload_check.elapsed() >= RELOAD_INTERVAL {
+            self.reloaded = self.assets.reload_changed();
+            self.last_reload_check = Instant::now();
+        }
+
+        if let Some(player) = &mut self.player {
+            player.play(self.frames, &self.event.event_sender(), &self.virtual_controllers)?;
+        }
+
+        Ok(true)
+    }
+
+    /// Number of frames started so far.
+    pub fn frames(&self) -> u32 {
+        self.frames
+    }
+
+    /// Number of fixed updates the lesson should run this frame.
+    pub fn ticks(&self) -> u32 {
+        self.main_loop.ticks()
+    }
+
+    /// Length of one fixed update.
+    pub fn tick(&self) -> Duration {
+        self.main_loop.tick()
+    }
+
+    /// How far this frame is between two fixed updates, for interpolating what is drawn.
+    pub fn alpha(&self) -> f32 {
+        self.main_loop.alpha()
+    }
+
+    /// Frame times measured over the last few frames.
+    pub fn stats(&self) -> &FrameStats {
+        self.main_loop.stats()
+    }
+
+    /// The actions input is mapped to, updated by [`App::poll_events`].
+    pub fn actions(&self) -> &ActionMap {
+        &self.actions
+    }
+
+    pub fn actions_mut(&mut self) -> &mut ActionMap {
+        &mut self.actions
+    }
+
+    /// File the actions' bindings were loaded from and are saved to, if any.
+    pub fn bindings_path(&self) -> Option<&Path> {
+        self.bindings_path.as_deref()
+    }
+
+    /// Saves the actions' bindings to [`App::bindings_path`], if there is one.
+    pub fn save_bindings(&self) -> Result<(), Error> {
+        match &self.bindings_path {
+            None => Ok(()),
+            Some(path) => self.actions.save(path)
+        }
+    }
+
+    /// Drains the event pump, recording the events if the app was asked to. Escape comes
+    /// out as a quit event if the app was built with [`AppBuilder::escape_quits`].
+    ///
+    /// While the queue is empty this waits for the first event until the next frame is
+    /// due, instead of spinning.
+    pub fn poll_events(&mut self) -> Result<Vec<Event>, Error> {
+        let mut events = Vec::new();
+        if let Some(left) = self.main_loop.time_left() {
+            let timeout = left.as_millis() as u32;
+            if timeout > 0 {
+                events.extend(self.event_pump.wait_event_timeout(timeout));
+            }
+        }
+        events.extend(self.event_pump.poll_iter());
+
+        for event in &events {
+            self.actions.handle_event(event);
+        }
+
+        if let Some(recorder) = &mut self.recorder {
+            for event in &events {
+                recorder.record(self.frames, event)?;
+            }
+            recorder.flush()?;
+        }
+
+        if self.escape_quits {
+            for event in &mut events {
+                if let Event::KeyDown { keycode: Some(Keycode::Escape), timestamp, .. } = *event {
+                    *event = Event::Quit { timestamp };
+                }
+            }
+        }
+
+        Ok(events)
+    }
+
+    /// Shows the current frame, through the canvas or the window surface depending on the mode.
+    ///
+    /// This is also where a requested [`Capture`] is taken, since a canvas can only be read
+    /// back before it is presented, and where the frame sleeps off the rest of its time.
+    pub fn present(&mut self) -> Result<(), Error> {
+        self.show()?;
+        self.main_loop.end_frame();
+        Ok(())
+    }
+
+    fn show(&mut self) -> Result<(), Error> {
+        let capture = match &self.capture {
+            Some(capture) if capture.frame == self.frames => Some(capture.path.clone()),
+            _ => None
+        };
+
+        match &mut self.target {
+            Target::Surface(window) => {
+                let surface = window.surface(&self.event_pump)
+                    .map_err(Error::sdl(Error::WindowSurface))?;
+                if let Some(path) = capture {
+                    Image::from_surface(&surface)
+                        .map_err(|message| Error::capture(&path, message))?
+                        .save(&path)?;
+                }
+                surface.update_window().map_err(Error::sdl(Error::Render))
+            },
+            Target::Renderer(canvas) => {
+                if let Some(path) = capture {
+                    Image::from_canvas(canvas)
+                        .map_err(|message| Error::capture(&path, message))?
+                        .save(&path)?;
+                }
+                canvas.present();
+                Ok(())
+            }
+        }
+    }
+
+    pub fn sdl(&self) -> &Sdl {
+        &self.sdl
+    }
+
+    pub fn event(&self) -> &EventSubsystem {
+        &self.event
+    }
+
+    pub fn video(&self) -> &VideoSubsystem {
+        &self.video
+    }
+
+    /// Returns the game controller subsystem, initializing it on first use.
+    pub fn game_controller(&mut self) -> Result<&GameControllerSubsystem, Error> {
+        if self.game_controller.is_none() {
+            let subsystem = self.sdl.game_controller()
+                .map_err(Error::sdl(Error::ControllerSubsystem))?;
+            self.game_controller = Some(subsystem);
+        }
+
+        Ok(self.game_controller.as_ref().unwrap())
+    }
+
+    /// Returns the joystick subsystem, initializing it on first use.
+    pub fn joystick(&mut self) -> Result<&JoystickSubsystem, Error> {
+        if self.joystick.is_none() {
+            let subsystem = self.sdl.joystick().map_err(Error::sdl(Error::JoystickSubsystem))?;
+            self.joystick = Some(subsystem);
+        }
+
+        Ok(self.joystick.as_ref().unwrap())
+    }
+
+    /// Plugs in a new [`VirtualController`], announced to the lesson like a real one.
+    pub fn attach_virtual_controller(&mut self) -> Result<&VirtualController, Error> {
+        let joystick = self.joystick()?.clone();
+        let game_controller = self.game_controller()?;
+        let controller = VirtualController::attach(&joystick, game_controller)?;
+        self.virtual_controllers.push(controller);
+
+        Ok(self.virtual_controllers.last().unwrap())
+    }
+
+    /// Unplugs the `n`th virtual controller, announced to the lesson like a real one.
+    pub fn detach_virtual_controller(&mut self, n: usize) {
+        if n < self.virtual_controllers.len() {
+            self.virtual_controllers.remove(n);
+        }
+    }
+
+    /// Virtual controllers plugged in so far, in the order they were attached.
+    pub fn virtual_controllers(&self) -> &[VirtualController] {
+        &self.virtual_controllers
+    }
+
+    /// Returns the audio subsystem, initializing it on first use.
+    pub fn audio(&mut self) -> Result<&AudioSubsystem, Error> {
+        if self.audio.is_none() {
+            let subsystem = self.sdl.audio().map_err(Error::sdl(Error::AudioSubsystem))?;
+            self.audio = Some(subsystem);
+        }
+
+        Ok(self.audio.as_ref().unwrap())
+    }
+
+    /// Returns SDL_ttf, initializing it on first use.
+    ///
+    /// Fonts borrow the returned handle while the app itself is borrowed mutably, so it is
+    /// shared; SDL_ttf quits once the app and every handle to it are dropped.
+    #[cfg(feature = "ttf")]
+    pub fn ttf(&mut self) -> Result<Rc<TrueType>, Error> {
+        if self.ttf.is_none() {
+            self.ttf = Some(Rc::new(TrueType::init()?));
+        }
+
+        Ok(Rc::clone(self.ttf.as_ref().unwrap()))
+    }
+
+    /// Returns the game controller subsystem with the app's mappings loaded.
+    ///
+    /// The first call reads [`CONTROLLER_MAPPINGS`], then [`Options::user_mappings`],
+    /// [`Options::mappings`] and `$SDL_GAMECONTROLLERCONFIG` over it, and hands SDL the mappings for the platform
+    /// it runs on. Malformed lines are reported as warnings and skipped.
+    pub fn mapped_game_controller(&mut self) -> Result<&GameControllerSubsystem, Error> {
+        if self.mappings.is_none() {
+            let mut mappings = MappingDb::load_asset(&self.assets, CONTROLLER_MAPPINGS)?;
+            if let Some(path) = self.user_mappings_path.as_deref().filter(|path| path.is_file()) {
+                mappings.merge(MappingDb::load(path)?);
+            }
+            for path in &self.mapping_files {
+                mappings.merge(MappingDb::load(path)?);
+            }
+            mappings.merge(MappingDb::from_env());
+
+            for error in mappings.errors() {
+                println!("Warning: Skipped controller mapping at {}", error);
+            }
+
+            let game_controller = self.game_controller()?;
+            match Platform::current() {
+                None => println!("Warning: No controller mappings for platform {}!", sdl2::get_platform()),
+                Some(platform) => {
+                    mappings.apply(game_controller, platform);
+                }
+            }
+            self.mappings = Some(mappings);
+        }
+
+        self.game_controller()
+    }
+
+    /// Every mapping [`App::mapped_game_controller`] loaded, once it has been called.
+    pub fn mappings(&self) -> Option<&MappingDb> {
+        self.mappings.as_ref()
+    }
+
+    /// File new controller mappings are appended to, if any.
+    pub fn user_mappings_path(&self) -> Option<&Path> {
+        self.user_mappings_path.as_deref()
+    }
+
+    /// Appends `mapping` to [`App::user_mappings_path`], if there is one, and hands it to
+    /// SDL so the controller can be opened with it right away.
+    pub fn add_mapping(&mut self, mapping: Mapping) -> Result<(), Error> {
+        if let Some(path) = &self.user_mappings_path {
+            MappingDb::append(path, &mapping)?;
+        }
+
+        self.mapped_game_controller()?
+            .add_mapping(&mapping.to_string())
+            .map_err(|source| Error::ControllerMapping { name: mapping.name.clone(), source })?;
+        if let Some(mappings) = &mut self.mappings {
+            mappings.insert(mapping);
+        }
+
+        Ok(())
+    }
+
+    pub fn assets(&mut self) -> &mut Assets {
+        &mut self.assets
+    }
+
+    /// Whether the asset at `path` was reloaded at the start of this frame.
+    pub fn was_reloaded(&self, path: &str) -> bool {
+        self.reloaded.iter().any(|reloaded| reloaded == path)
+    }
+
+    pub fn event_pump(&mut self) -> &mut EventPump {
+        &mut self.event_pump
+    }
+
+    pub fn window(&self) -> &Window {
+        match &self.target {
+            Target::Surface(window) => window,
+            Target::Renderer(canvas) => canvas.window(),
+        }
+    }
+
+    /// Returns the window surface of an app built in surface mode.
+    pub fn window_surface(&self) -> Result<WindowSurfaceRef<'_>, Error> {
+        self.window().surface(&self.event_pump).map_err(Error::sdl(Error::WindowSurface))
+    }
+
+    /// Returns the canvas of an app built in renderer mode.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the app was built without [`AppBuilder::renderer`].
+    pub fn canvas(&mut self) -> &mut WindowCanvas {
+        match &mut self.target {
+            Target::Surface(_) => panic!("App was built in surface mode, it has no canvas!"),
+            Target::Renderer(canvas) => canvas,
+        }
+    }
+
+    /// Borrows the canvas and the assets together, so textures can be made while drawing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the app was built without [`AppBuilder::renderer`].
+    pub fn canvas_and_assets(&mut self) -> (&mut WindowCanvas, &mut Assets) {
+        match &mut self.target {
+            Target::Surface(_) => panic!("App was built in surface mode, it has no canvas!"),
+            Target::Renderer(canvas) => (canvas, &mut self.assets),
+        }
+    }
+
+    pub fn texture_creator(&mut self) -> TextureCreator<WindowContext> {
+        self.canvas().texture_creator()
+    }
+}
@@ -0,0 +1,209 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::SystemTime;
+use sdl2::image::ImageRWops;
+use sdl2::render::{Texture, TextureCreator};
+use sdl2::rwops::RWops;
+use sdl2::surface::{Surface, SurfaceRef};
+use sdl2::video::WindowContext;
+use crate::Error;
+use crate::lazy_foo::embedded;
+
+/// Environment variable naming the directory assets are loaded from.
+pub const ASSET_ROOT_VAR: &str = "SDL2_LEARN_ASSETS";
+
+/// A loaded surface, shared by everything that asked for the same path.
+pub type SurfaceHandle = Rc<Surface<'static>>;
+
+/// A loaded texture, shared by everything that asked for the same path.
+pub type TextureHandle<'a> = Rc<Texture<'a>>;
+
+/// A cached surface, with the file it came from so it can be reloaded when it changes.
+struct Entry {
+    surface: SurfaceHandle,
+    generation: u32,
+    file: Option<(PathBuf, Option<SystemTime>)>,
+}
+
+/// Finds asset files and caches the surfaces loaded from them.
+///
+/// Paths like `imgs/key_presses/up.bmp` are looked up under each root in turn: the root
+/// given explicitly (`--assets`), then `$SDL2_LEARN_ASSETS`, then the current directory and
+/// finally the directory holding the executable. Binaries built with the `embed-assets`
+/// feature fall back to their embedded copy when no root has the file.
+///
+/// Surfaces loaded from disk are reloaded by [`Assets::reload_changed`] when their file is
+/// modified; holders of an old handle should ask for the path again to get the new one.
+pub struct Assets {
+    roots: Vec<PathBuf>,
+    surfaces: HashMap<String, Entry>,
+}
+
+impl Assets {
+    pub fn new(root: Option<&Path>) -> Assets {
+        let mut roots = Vec::new();
+        roots.extend(root.map(Path::to_path_buf));
+        roots.extend(env::var_os(ASSET_ROOT_VAR).map(PathBuf::from));
+        roots.extend(env::current_dir().ok());
+        roots.extend(env::current_exe().ok().and_then(|exe| exe.parent().map(Path::to_path_buf)));
+        roots.dedup();
+
+        Assets::with_roots(roots)
+    }
+
+    pub fn with_roots(roots: Vec<PathBuf>) -> Assets {
+        Assets { roots, surfaces: HashMap::new() }
+    }
+
+    pub fn roots(&self) -> &[PathBuf] {
+        &self.roots
+    }
+
+    /// Returns the full path of the first root containing `path`.
+    pub fn resolve(&self, path: &str) -> Result<PathBuf, Error> {
+        self.roots.iter()
+            .map(|root| root.join(path))
+            .find(|candidate| candidate.is_file())
+            .ok_or_else(|| Error::AssetNotFound {
+                path: path.to_string(),
+                searched: self.roots.clone(),
+            })
+    }
+
+    /// Opens `path` from the first root containing it, or from the embedded assets.
+    pub fn open(&self, path: &str) -> Result<RWops<'static>, Error> {
+        match self.resolve(path) {
+            Ok(full_path) => RWops::from_file(full_path, "rb").map_err(Error::asset_load(path)),
+            Err(not_found) => match embedded::find(path) {
+                None => Err(not_found),
+                Some(contents) => RWops::from_bytes(contents).map_err(Error::asset_load(path))
+            }
+        }
+    }
+
+    /// Loads a BMP (or, with SDL_image, any other image) once and shares it afterwards.
+    pub fn surface(&mut self, path: &str) -> Result<SurfaceHandle, Error> {
+        if let Some(entry) = self.surfaces.get(path) {
+            return Ok(entry.surface.clone());
+        }
+
+        let file = self.resolve(path).ok().map(|full_path| {
+            let modified = modified(&full_path);
+            (full_path, modified)
+        });
+
+        let mut rwops = self.open(path)?;
+        let surface = load_surface(path, &mut rwops).map_err(Error::asset_load(path))?;
+        let surface = Rc::new(surface);
+        self.surfaces.insert(path.to_string(), Entry { surface: surface.clone(), generation: 0, file });
+        Ok(surface)
+    }
+
+    /// How many times the surface at `path` has been reloaded, if it is loaded at all.
+    pub fn generation(&self, path: &str) -> Option<u32> {
+        self.surfaces.get(path).map(|entry| entry.generation)
+    }
+
+    /// Reloads every cached surface whose file changed on disk, returning their paths.
+    ///
+    /// A file that fails to decode (e.g. because it is still being written) is reported and
+    /// the previous surface is kept until the file changes again.
+    pub fn reload_changed(&mut self) -> Vec<String> {
+        let mut reloaded = Vec::new();
+
+        for (path, entry) in &mut self.surfaces {
+            let (full_path, last_modified) = match &mut entry.file {
+                None => continue,
+                Some(file) => file
+            };
+
+            let current = modified(full_path);
+            if current == *last_modified {
+                continue;
+            }
+            *last_modified = current;
+
+            let loaded = RWops::from_file(&full_path, "rb")
+                .and_then(|mut rwops| load_surface(path, &mut rwops));
+            match loaded {
+                Err(error) => {
+                    println!("Warning: Unable to reload {}, keeping the previous version! SDL Error: {}", path, error);
+                },
+                Ok(surface) => {
+                    entry.surface = Rc::new(surface);
+                    entry.generation += 1;
+                    reloaded.push(path.clone());
+                }
+            }
+        }
+
+        reloaded
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+fn load_surface(path: &str, rwops: &mut RWops) -> Result<Surface<'static>, String> {
+    match Path::new(path).extension() {
+        Some(extension) if extension.eq_ignore_ascii_case("bmp") => Surface::load_bmp_rw(rwops),
+        _ => rwops.load()
+    }
+}
+
+/// Caches textures created from the surfaces of an [`Assets`].
+///
+/// A texture is re-created when its surface has been reloaded since it was made.
+pub struct TextureCache<'a> {
+    texture_creator: &'a TextureCreator<WindowContext>,
+    textures: HashMap<String, (u32, TextureHandle<'a>)>,
+    /// Textures made from surfaces that are not assets, like font atlases.
+    generated: HashMap<String, Texture<'a>>,
+}
+
+impl<'a> TextureCache<'a> {
+    pub fn new(texture_creator: &'a TextureCreator<WindowContext>) -> TextureCache<'a> {
+        TextureCache { texture_creator, textures: HashMap::new(), generated: HashMap::new() }
+    }
+
+    /// Returns the texture kept under `key`, made from the surface `surface` returns the
+    /// first time it is asked for.
+    pub fn generated<S: AsRef<SurfaceRef>>(&mut self, key: &str, surface: impl FnOnce() -> Result<S, Error>)
+        -> Result<&mut Texture<'a>, Error>
+    {
+        if !self.generated.contains_key(key) {
+            let texture = self.texture_creator.create_texture_from_surface(surface()?)
+                .map_err(|source| Error::TextureCreate { path: key.to_string(), source })?;
+            self.generated.insert(key.to_string(), texture);
+        }
+
+        Ok(self.generated.get_mut(key).unwrap())
+    }
+
+    /// Drops the texture kept under `key`, to be made again the next time it is asked for.
+    pub fn forget(&mut self, key: &str) {
+        self.generated.remove(key);
+    }
+
+    pub fn texture(&mut self, assets: &mut Assets, path: &str) -> Result<TextureHandle<'a>, Error> {
+        let surface = assets.surface(path)?;
+        let generation = assets.generation(path).unwrap_or_default();
+
+        if let Some((made_from, texture)) = self.textures.get(path) {
+            if *made_from == generation {
+                return Ok(texture.clone());
+            }
+        }
+
+        let texture = self.texture_creator.create_texture_from_surface(&*surface)
+            .map_err(|source| Error::TextureCreate { path: path.to_string(), source })?;
+
+        let texture = Rc::new(texture);
+        self.textures.insert(path.to_string(), (generation, texture.clone()));
+        Ok(texture)
+    }
+}
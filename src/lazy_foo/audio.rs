@@ -0,0 +1,105 @@
+//! Sound effects and music through SDL_mixer, enabled by the `mixer` feature.
+
+use sdl2::mixer::{self, Channel, Chunk, LoaderRWops, Music, Sdl2MixerContext, AUDIO_S16LSB};
+use crate::Error;
+use crate::error::SdlError;
+use crate::lazy_foo::app::App;
+use crate::lazy_foo::assets::Assets;
+use crate::lazy_foo::embedded;
+
+pub const FREQUENCY: i32 = 44100;
+pub const CHANNELS: i32 = 2;
+/// Samples per channel in the device's buffer, about 23 ms at [`FREQUENCY`].
+pub const CHUNK_SIZE: i32 = 1024;
+/// Number of effects that can play at the same time.
+pub const MIXING_CHANNELS: i32 = 8;
+/// Loudest volume SDL_mixer accepts for a channel or the music.
+pub const MAX_VOLUME: i32 = mixer::MAX_VOLUME;
+
+/// An open audio device with SDL_mixer on top of it; closed again when dropped.
+///
+/// Effects and music have to be dropped before the mixer.
+pub struct Mixer {
+    /// Set when OGG support could be loaded; WAV works without it.
+    _context: Option<Sdl2MixerContext>,
+}
+
+impl Mixer {
+    /// Opens the default audio device (or SDL's `dummy`/`disk` driver in headless runs).
+    pub fn open(app: &mut App) -> Result<Mixer, Error> {
+        app.audio()?;
+
+        // Decoders are loaded before the device opens, as SDL_mixer asks.
+        let context = match mixer::init(mixer::InitFlag::OGG) {
+            Err(message) => {
+                println!("Warning: OGG support could not be loaded, only WAV will play! SDL_mixer Error: {}", message);
+                None
+            },
+            Ok(context) => Some(context)
+        };
+        mixer::open_audio(FREQUENCY, AUDIO_S16LSB, CHANNELS, CHUNK_SIZE).map_err(Error::sdl(Error::MixerInit))?;
+        mixer::allocate_channels(MIXING_CHANNELS);
+
+        Ok(Mixer { _context: context })
+    }
+
+    /// Loads a WAV or OGG file as an effect.
+    pub fn load_effect(&self, assets: &Assets, path: &str) -> Result<Chunk, Error> {
+        assets.open(path)?
+            .load_wav()
+            .map_err(|message| Error::SoundLoad { path: path.to_string(), source: SdlError(message) })
+    }
+
+    /// Loads a WAV or OGG file as music, streamed while it plays.
+    pub fn load_music(&self, assets: &Assets, path: &str) -> Result<Music<'static>, Error> {
+        let music = match assets.resolve(path) {
+            Ok(full_path) => Music::from_file(full_path),
+            Err(not_found) => match embedded::find(path) {
+                None => return Err(not_found),
+                Some(contents) => Music::from_static_bytes(contents)
+            }
+        };
+
+        music.map_err(|message| Error::SoundLoad { path: path.to_string(), source: SdlError(message) })
+    }
+
+    /// Plays `effect` once on the first free channel.
+    ///
+    /// `volume` goes from 0 to [`MAX_VOLUME`] and `pan` from -1.0 (left) to 1.0 (right).
+    pub fn play(&self, effect: &Chunk, volume: i32, pan: f32) -> Result<Channel, Error> {
+        let channel = Channel::all().play(effect, 0).map_err(Error::sdl(Error::SoundPlay))?;
+        channel.set_volume(volume.clamp(0, MAX_VOLUME));
+
+        let pan = pan.clamp(-1.0, 1.0);
+        let left = (255.0 * (1.0 - pan.max(0.0))) as u8;
+        let right = (255.0 * (1.0 + pan.min(0.0))) as u8;
+        channel.set_panning(left, right).map_err(Error::sdl(Error::SoundPlay))?;
+
+        Ok(channel)
+    }
+
+    /// Starts `music` from the start, looping forever, or resumes it if it was paused.
+    pub fn toggle_music(&self, music: &Music) -> Result<(), Error> {
+        if !Music::is_playing() {
+            music.play(-1).map_err(Error::sdl(Error::SoundPlay))
+        } else if Music::is_paused() {
+            Music::resume();
+            Ok(())
+        } else {
+            Music::pause();
+            Ok(())
+        }
+    }
+
+    pub fn stop_music(&self) {
+        Music::halt();
+    }
+}
+
+impl Drop for Mixer {
+    fn drop(&mut self) {
+        Channel::all().halt();
+        Music::halt();
+        mixer::close_audio();
+    }
+}
@@ -0,0 +1,157 @@
+use std::path::{Path, PathBuf};
+use sdl2::image::{LoadSurface, SaveSurface};
+use sdl2::pixels::PixelFormatEnum;
+use sdl2::render::WindowCanvas;
+use sdl2::surface::{Surface, SurfaceRef};
+use crate::Error;
+
+/// Asks the app to save what it presents on `frame` (1-based) to `path`.
+///
+/// Paths ending in `.bmp` are saved as BMP, anything else as PNG.
+#[derive(Debug, Clone)]
+pub struct Capture {
+    pub frame: u32,
+    pub path: PathBuf,
+}
+
+/// A tightly packed RGBA32 image, independent of any SDL surface or renderer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+/// Result of comparing two images channel by channel.
+pub struct Comparison {
+    /// Number of pixels with at least one channel off by more than the tolerance.
+    pub mismatched: usize,
+    /// Largest difference found on any channel.
+    pub max_delta: u8,
+    /// The expected image greyed out, with mismatching pixels painted red.
+    pub diff: Image,
+}
+
+impl Comparison {
+    pub fn matches(&self) -> bool {
+        self.mismatched == 0
+    }
+}
+
+impl Image {
+    pub fn new(width: u32, height: u32, pixels: Vec<u8>) -> Image {
+        assert_eq!(pixels.len(), (width * height * 4) as usize, "Image data is not RGBA32!");
+        Image { width, height, pixels }
+    }
+
+    /// Reads back what has been rendered to `canvas` since the last present.
+    pub fn from_canvas(canvas: &WindowCanvas) -> Result<Image, String> {
+        let (width, height) = canvas.output_size()?;
+        let pixels = canvas.read_pixels(None, PixelFormatEnum::RGBA32)?;
+
+        Ok(Image::new(width, height, pixels))
+    }
+
+    pub fn from_surface(surface: &SurfaceRef) -> Result<Image, String> {
+        let surface = surface.convert_format(PixelFormatEnum::RGBA32)?;
+        let row_len = (surface.width() * 4) as usize;
+        let pitch = surface.pitch() as usize;
+
+        let pixels = surface.with_lock(|data| {
+            data.chunks(pitch)
+                .take(surface.height() as usize)
+                .flat_map(|row| &row[..row_len])
+                .copied()
+                .collect()
+        });
+
+        Ok(Image::new(surface.width(), surface.height(), pixels))
+    }
+
+    /// Loads any format SDL_image understands.
+    pub fn load(path: &Path) -> Result<Image, Error> {
+        let display = path.display().to_string();
+        let surface = Surface::from_file(path).map_err(Error::asset_load(&display))?;
+
+        Image::from_surface(&surface).map_err(Error::surface_convert(&display))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        let mut pixels = self.pixels.clone();
+        let surface = Surface::from_data(
+            &mut pixels,
+            self.width,
+            self.height,
+            self.width * 4,
+            PixelFormatEnum::RGBA32
+        ).map_err(|message| Error::capture(path, message))?;
+
+        let saved = match path.extension() {
+            Some(extension) if extension.eq_ignore_ascii_case("bmp") => surface.save_bmp(path),
+            _ => surface.save(path)
+        };
+
+        saved.map_err(|message| Error::capture(path, message))
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the RGBA value at (`x`, `y`), or `None` outside the image.
+    pub fn pixel(&self, x: u32, y: u32) -> Option<[u8; 4]> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        let offset = ((y * self.width + x) * 4) as usize;
+        let mut pixel = [0; 4];
+        pixel.copy_from_slice(&self.pixels[offset..offset + 4]);
+        Some(pixel)
+    }
+
+    /// Compares against `expected`, allowing each channel to differ by `tolerance`.
+    ///
+    /// Pixels covered by only one of the two images always count as mismatched.
+    pub fn compare(&self, expected: &Image, tolerance: u8) -> Comparison {
+        let width = self.width.max(expected.width);
+        let height = self.height.max(expected.height);
+        let mut diff = Vec::with_capacity((width * height * 4) as usize);
+        let mut mismatched = 0;
+        let mut max_delta = 0;
+
+        for y in 0..height {
+            for x in 0..width {
+                let (actual, wanted) = match (self.pixel(x, y), expected.pixel(x, y)) {
+                    (Some(actual), Some(wanted)) => (actual, wanted),
+                    _ => {
+                        mismatched += 1;
+                        max_delta = u8::MAX;
+                        diff.extend_from_slice(&[0xFF, 0x00, 0x00, 0xFF]);
+                        continue;
+                    }
+                };
+
+                let delta = actual.iter().zip(wanted.iter())
+                    .map(|(a, b)| a.abs_diff(*b))
+                    .max()
+                    .unwrap_or(0);
+                max_delta = max_delta.max(delta);
+
+                if delta > tolerance {
+                    mismatched += 1;
+                    diff.extend_from_slice(&[0xFF, 0x00, 0x00, 0xFF]);
+                } else {
+                    let grey = ((wanted[0] as u32 + wanted[1] as u32 + wanted[2] as u32) / 6) as u8;
+                    diff.extend_from_slice(&[grey, grey, grey, 0xFF]);
+                }
+            }
+        }
+
+        Comparison { mismatched, max_delta, diff: Image::new(width, height, diff) }
+    }
+}
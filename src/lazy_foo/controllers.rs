@@ -0,0 +1,264 @@
+use std::collections::{HashMap, HashSet};
+use std::ffi::CStr;
+use sdl2::sys;
+use sdl2::controller::{Axis, Button, GameController};
+use sdl2::event::Event;
+use sdl2::joystick::Joystick;
+use crate::Error;
+use crate::lazy_foo::app::App;
+
+/// Number of players that can play at once; more controllers wait for a free slot.
+pub const MAX_PLAYERS: usize = 4;
+
+/// A controller assigned to a player slot, with what it currently holds.
+pub struct Player {
+    slot: usize,
+    guid: String,
+    serial: Option<String>,
+    controller: GameController,
+    joystick: Joystick,
+    buttons: HashSet<Button>,
+    axes: HashMap<Axis, i16>,
+    last_button: Option<Button>,
+}
+
+impl Player {
+    /// The player's slot, from 0 to [`MAX_PLAYERS`] - 1.
+    pub fn slot(&self) -> usize {
+        self.slot
+    }
+
+    pub fn name(&self) -> String {
+        self.controller.name()
+    }
+
+    pub fn guid(&self) -> &str {
+        &self.guid
+    }
+
+    /// The device's serial number, when its driver reports one.
+    pub fn serial(&self) -> Option<&str> {
+        self.serial.as_deref()
+    }
+
+    pub fn instance_id(&self) -> u32 {
+        self.controller.instance_id()
+    }
+
+    pub fn controller(&self) -> &GameController {
+        &self.controller
+    }
+
+    /// The same device as a plain joystick, to read its buttons and axes before mapping.
+    pub fn joystick(&self) -> &Joystick {
+        &self.joystick
+    }
+
+    pub fn pressed(&self, button: Button) -> bool {
+        self.buttons.contains(&button)
+    }
+
+    /// Last reported position of `axis`, 0 until it moves.
+    pub fn axis(&self, axis: Axis) -> i16 {
+        self.axes.get(&axis).copied().unwrap_or(0)
+    }
+
+    /// The button this player pressed last, if any.
+    pub fn last_button(&self) -> Option<Button> {
+        self.last_button
+    }
+}
+
+/// Opens every connected controller and keeps each in the same player slot while it is
+/// plugged in.
+///
+/// A controller unplugged and plugged back in gets its old slot back, unless another
+/// controller took it in the meantime. Controllers are told apart by serial number, or by
+/// GUID when they have none, in which case two controllers of the same model can swap slots.
+pub struct Controllers {
+    slots: Vec<Option<Player>>,
+    /// Serial number (or GUID) of the controller that last had each slot.
+    previous: Vec<Option<String>>,
+}
+
+impl Default for Controllers {
+    fn default() -> Controllers {
+        Controllers::new()
+    }
+}
+
+impl Controllers {
+    pub fn new() -> Controllers {
+        Controllers {
+            slots: (0..MAX_PLAYERS).map(|_| None).collect(),
+            previous: vec![None; MAX_PLAYERS],
+        }
+    }
+
+    /// Loads the app's controller mappings and opens every controller already connected.
+    pub fn open_all(&mut self, app: &mut App) -> Result<(), Error> {
+        let num_joysticks = app.mapped_game_controller()?
+            .num_joysticks()
+            .map_err(Error::sdl(Error::ControllerSubsystem))?;
+
+        for index in 0..num_joysticks {
+            self.open(app, index)?;
+        }
+
+        Ok(())
+    }
+
+    /// Opens the controller at device `index` into a free slot, returning the slot.
+    pub fn open(&mut self, app: &mut App, index: u32) -> Result<Option<usize>, Error> {
+        let game_controller = app.game_controller()?;
+        if !game_controller.is_game_controller(index) {
+            return Ok(None);
+        }
+
+        let controller = match game_controller.open(index) {
+            Err(error) => {
+                println!("Warning: Unable to open game controller {}! SDL Error: {}", index, error);
+                return Ok(None);
+            },
+            Ok(controller) => controller
+        };
+
+        // Controllers connected before the app started are announced again as added.
+        if let Some(player) = self.player_for(controller.instance_id()) {
+            return Ok(Some(player.slot));
+        }
+
+        let joystick = match app.joystick()?.open(index) {
+            Err(error) => {
+                println!("Warning: Unable to open joystick {}! SDL Error: {}", index, error);
+                return Ok(None);
+            },
+            Ok(joystick) => joystick
+        };
+
+        // The serial number names the device itself; the GUID only names its model, but
+        // stays the same across replugs as well.
+        let guid = joystick.guid().string();
+        let serial = serial(joystick.instance_id());
+        let identity = serial.clone().unwrap_or_else(|| guid.clone());
+        let free = |slot: &usize| self.slots[*slot].is_none();
+        let slot = (0..MAX_PLAYERS).filter(free).find(|&slot| self.previous[slot].as_deref() == Some(identity.as_str()))
+            .or_else(|| (0..MAX_PLAYERS).filter(free).find(|&slot| self.previous[slot].is_none()))
+            .or_else(|| (0..MAX_PLAYERS).find(free));
+
+        let slot = match slot {
+            None => {
+                println!("Warning: No free player slot for game controller {}!", controller.name());
+                return Ok(None);
+            },
+            Some(slot) => slot
+        };
+
+        self.previous[slot] = Some(identity);
+        self.slots[slot] = Some(Player {
+            slot,
+            guid,
+            serial,
+            controller,
+            joystick,
+            buttons: HashSet::new(),
+            axes: HashMap::new(),
+            last_button: None,
+        });
+
+        Ok(Some(slot))
+    }
+
+    /// Closes every controller; their slots are kept for when they are opened again.
+    pub fn close_all(&mut self) {
+        for slot in &mut self.slots {
+            *slot = None;
+        }
+    }
+
+    /// Opens added controllers, closes removed ones and tracks what each player holds.
+    pub fn handle_event(&mut self, app: &mut App, event: &Event) -> Result<(), Error> {
+        match *event {
+            Event::ControllerDeviceAdded { which, .. } => {
+                self.open(app, which)?;
+            },
+            Event::ControllerDeviceRemoved { which, .. } => {
+                let mut freed = false;
+                for slot in &mut self.slots {
+                    if slot.as_ref().is_some_and(|player| player.instance_id() == which) {
+                        *slot = None;
+                        freed = true;
+                    }
+                }
+
+                // A controller waiting for a slot takes the one just freed.
+                if freed {
+                    self.open_all(app)?;
+                }
+            },
+            Event::ControllerDeviceRemapped { which, .. } => {
+                if let Some(player) = self.player_for_mut(which) {
+                    // Held buttons may not be the same buttons under the new mapping.
+                    player.buttons.clear();
+                    player.axes.clear();
+                }
+            },
+            Event::ControllerButtonDown { which, button, .. } => {
+                if let Some(player) = self.player_for_mut(which) {
+                    player.buttons.insert(button);
+                    player.last_button = Some(button);
+                }
+            },
+            Event::ControllerButtonUp { which, button, .. } => {
+                if let Some(player) = self.player_for_mut(which) {
+                    player.buttons.remove(&button);
+                }
+            },
+            Event::ControllerAxisMotion { which, axis, value, .. } => {
+                if let Some(player) = self.player_for_mut(which) {
+                    player.axes.insert(axis, value);
+                }
+            },
+            _ => ()
+        }
+
+        Ok(())
+    }
+
+    /// Connected players, by slot.
+    pub fn players(&self) -> impl Iterator<Item = &Player> {
+        self.slots.iter().flatten()
+    }
+
+    pub fn player(&self, slot: usize) -> Option<&Player> {
+        self.slots.get(slot).and_then(Option::as_ref)
+    }
+
+    /// The player using the controller with joystick instance id `which`.
+    pub fn player_for(&self, which: u32) -> Option<&Player> {
+        self.players().find(|player| player.instance_id() == which)
+    }
+
+    fn player_for_mut(&mut self, which: u32) -> Option<&mut Player> {
+        self.slots.iter_mut().flatten().find(|player| player.instance_id() == which)
+    }
+
+    pub fn len(&self) -> usize {
+        self.players().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Serial number of the open joystick with instance id `which`, if its driver reports one.
+fn serial(which: u32) -> Option<String> {
+    let serial = unsafe { sys::SDL_JoystickGetSerial(sys::SDL_JoystickFromInstanceID(which as i32)) };
+    if serial.is_null() {
+        return None;
+    }
+
+    let serial = unsafe { CStr::from_ptr(serial) }.to_string_lossy().into_owned();
+    (!serial.is_empty()).then_some(serial)
+}
@@ -1,67 +1,32 @@
 use sdl2::event::Event;
 use sdl2::pixels::Color;
+use crate::Error;
+use crate::lazy_foo::app::{App, AppBuilder, Options};
 
 
-pub fn run() {
-    const SCREEN_WIDTH: u32 = 640;
-    const SCREEN_HEIGHT: u32 = 480;
+pub fn run(options: &Options) -> Result<(), Error> {
+    let mut app = AppBuilder::new("SDL Tutorial")
+        .options(options)
+        .build()?;
 
-    let sdl_ctx = match sdl2::init() {
-        Err(error) => {
-            println!("SDL could not initialize! SDL_Error: {error}");
-            return;
-        }
-        Ok(sdl) => sdl
-    };
-
-    let video_subsystem = match sdl_ctx.video() {
-        Err(error) => {
-            println!("Window could not be created! SDL_Error: {error}");
-            return;
-        }
-        Ok(video_subsystem) => video_subsystem
-    };
+    run_in(&mut app)
+}
 
-    let windows_builder = video_subsystem
-        .window("SDL Tutorial", SCREEN_WIDTH, SCREEN_HEIGHT);
+pub fn run_in(app: &mut App) -> Result<(), Error> {
+    let mut screen_surface = app.window_surface()?;
 
-    let window = match windows_builder.build() {
-        Err(error) => {
-            println!("Window could not be created! SDL_Error: {error}");
-            return;
-        }
-        Ok(window) => window
-    };
+    screen_surface.fill_rect(None, Color::RGB(0xFF, 0xFF, 0xFF))
+        .map_err(Error::sdl(Error::Render))?;
 
-    let mut event_pump = match sdl_ctx.event_pump() {
-        Err(error) => {
-            println!("Window could not be created! SDL_Error: {error}");
-            return;
+    'running: while app.next_frame()? {
+        for event in app.poll_events()? {
+            if let Event::Quit {..} = event {
+                break 'running;
+            }
         }
-        Ok(event_pump) => event_pump
-    };
 
-    let mut screen_surface = match window.surface(&event_pump) {
-        Err(_error) => return,
-        Ok(screen_surface) => screen_surface
-    };
-
-    match screen_surface.fill_rect(None, Color::RGB(0xFF, 0xFF, 0xFF)) {
-        Err(_error) => return,
-        _ => ()
-    }
-
-    match screen_surface.update_window() {
-        Err(_error) => return,
-        _ => ()
+        app.present()?;
     }
 
-    'running: loop {
-        for event in event_pump.poll_iter() {
-            match event {
-                Event::Quit {..} => break 'running,
-                _ => ()
-            }
-        }
-    }
+    Ok(())
 }
@@ -0,0 +1,23 @@
+use sdl2::rect::Point;
+use sdl2::render::WindowCanvas;
+use crate::Error;
+
+/// Outlines a circle in the canvas' draw color.
+pub fn draw_circle(canvas: &mut WindowCanvas, x: i32, y: i32, radius: i32) -> Result<(), Error> {
+    let points: Vec<Point> = (0..=64)
+        .map(|step| {
+            let angle = step as f32 / 64.0 * std::f32::consts::TAU;
+            Point::new(x + (angle.cos() * radius as f32).round() as i32, y + (angle.sin() * radius as f32).round() as i32)
+        })
+        .collect();
+    canvas.draw_lines(points.as_slice()).map_err(Error::sdl(Error::Render))
+}
+
+/// Fills a circle in the canvas' draw color, one horizontal line per row.
+pub fn fill_circle(canvas: &mut WindowCanvas, x: i32, y: i32, radius: i32) -> Result<(), Error> {
+    for dy in -radius..=radius {
+        let dx = ((radius * radius - dy * dy) as f32).sqrt() as i32;
+        canvas.draw_line((x - dx, y + dy), (x + dx, y + dy)).map_err(Error::sdl(Error::Render))?;
+    }
+    Ok(())
+}
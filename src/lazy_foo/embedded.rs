@@ -0,0 +1,45 @@
+//! Assets compiled into the binary by the `embed-assets` feature.
+
+macro_rules! embed {
+    ($($path:literal),* $(,)?) => {
+        &[$(($path, include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/", $path)))),*]
+    };
+}
+
+#[cfg(feature = "embed-assets")]
+const FILES: &[(&str, &[u8])] = embed![
+    "controller_mapping.txt",
+    "imgs/event_driven/x.bmp",
+    "imgs/fonts/font8x8.fnt",
+    "imgs/fonts/font8x8.png",
+    "imgs/gamepads_and_joysticks/A.bmp",
+    "imgs/gamepads_and_joysticks/B.bmp",
+    "imgs/gamepads_and_joysticks/X.bmp",
+    "imgs/gamepads_and_joysticks/Y.bmp",
+    "imgs/gamepads_and_joysticks/press.bmp",
+    "imgs/image_screen/hello_world.bmp",
+    "imgs/key_presses/down.bmp",
+    "imgs/key_presses/left.bmp",
+    "imgs/key_presses/press.bmp",
+    "imgs/key_presses/right.bmp",
+    "imgs/key_presses/up.bmp",
+    "imgs/loading_other_image/loaded.png",
+    "imgs/optimized_surface/stretch.bmp",
+    "imgs/sound_effects_and_music/high.wav",
+    "imgs/sound_effects_and_music/low.wav",
+    "imgs/sound_effects_and_music/medium.wav",
+    "imgs/sound_effects_and_music/music.wav",
+    "imgs/sound_effects_and_music/scratch.wav",
+    "imgs/texture_loading/texture.png",
+    "imgs/true_type_fonts/DejaVuSansMono.ttf",
+];
+
+#[cfg(not(feature = "embed-assets"))]
+const FILES: &[(&str, &[u8])] = embed![];
+
+/// Returns the contents of an embedded asset, if the binary was built with it.
+pub fn find(path: &str) -> Option<&'static [u8]> {
+    FILES.iter()
+        .find(|(embedded, _)| *embedded == path)
+        .map(|(_, contents)| *contents)
+}
@@ -0,0 +1,232 @@
+use sdl2::controller::{Axis, Button};
+use sdl2::event::Event;
+use sdl2::keyboard::Keycode;
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::WindowCanvas;
+use crate::Error;
+use crate::lazy_foo::app::{App, Options, SCREEN_WIDTH};
+use crate::lazy_foo::controllers::{Controllers, Player};
+use crate::lazy_foo::draw::{draw_circle, fill_circle};
+use crate::lazy_foo::scene::{self, Scene, Textures, Transition};
+use crate::lazy_foo::text::{Align, Font, FontTexture, TextStyle};
+
+const STICK_RADIUS: i32 = 44;
+const DOT_RADIUS: i32 = 6;
+const LEFT_STICK: (i32, i32) = (230, 320);
+const RIGHT_STICK: (i32, i32) = (410, 320);
+
+const TRIGGER_WIDTH: u32 = 24;
+const TRIGGER_HEIGHT: u32 = 64;
+const LEFT_TRIGGER: (i32, i32) = (124, 56);
+const RIGHT_TRIGGER: (i32, i32) = (492, 56);
+
+const BACKGROUND: Color = Color::RGB(0x10, 0x10, 0x30);
+const OUTLINE: Color = Color::RGB(0x80, 0x80, 0x80);
+const HELD: Color = Color::YELLOW;
+const TEXT: Color = Color::WHITE;
+
+/// Where a button is drawn: a circle around its center, or a box from its corner.
+enum Shape {
+    Circle(i32, i32, i32),
+    Box(i32, i32, u32, u32),
+}
+
+/// Every button with its label and shape on the diagram.
+const BUTTONS: [(Button, &str, Shape); 21] = [
+    (Button::A, "A", Shape::Circle(490, 258, 14)),
+    (Button::B, "B", Shape::Circle(518, 230, 14)),
+    (Button::X, "X", Shape::Circle(462, 230, 14)),
+    (Button::Y, "Y", Shape::Circle(490, 202, 14)),
+    (Button::Back, "BACK", Shape::Box(248, 222, 44, 20)),
+    (Button::Guide, "HOME", Shape::Box(298, 214, 44, 36)),
+    (Button::Start, "START", Shape::Box(348, 222, 48, 20)),
+    (Button::LeftStick, "LS", Shape::Circle(LEFT_STICK.0, LEFT_STICK.1, STICK_RADIUS)),
+    (Button::RightStick, "RS", Shape::Circle(RIGHT_STICK.0, RIGHT_STICK.1, STICK_RADIUS)),
+    (Button::LeftShoulder, "LB", Shape::Box(88, 132, 96, 20)),
+    (Button::RightShoulder, "RB", Shape::Box(456, 132, 96, 20)),
+    (Button::DPadUp, "U", Shape::Box(138, 196, 24, 24)),
+    (Button::DPadDown, "D", Shape::Box(138, 244, 24, 24)),
+    (Button::DPadLeft, "L", Shape::Box(114, 220, 24, 24)),
+    (Button::DPadRight, "R", Shape::Box(162, 220, 24, 24)),
+    (Button::Misc1, "MISC", Shape::Box(128, 380, 56, 20)),
+    (Button::Paddle1, "P1", Shape::Box(192, 380, 40, 20)),
+    (Button::Paddle2, "P2", Shape::Box(240, 380, 40, 20)),
+    (Button::Paddle3, "P3", Shape::Box(360, 380, 40, 20)),
+    (Button::Paddle4, "P4", Shape::Box(408, 380, 40, 20)),
+    (Button::Touchpad, "TOUCH", Shape::Box(456, 380, 56, 20)),
+];
+
+const AXES: [(Axis, &str); 6] = [
+    (Axis::LeftX, "LX"), (Axis::LeftY, "LY"), (Axis::RightX, "RX"), (Axis::RightY, "RY"),
+    (Axis::TriggerLeft, "LT"), (Axis::TriggerRight, "RT"),
+];
+
+/// Where a stick's dot sits for its two axis values, kept inside the stick's circle.
+fn stick_dot(center: (i32, i32), x: i16, y: i16) -> (i32, i32) {
+    let travel = (STICK_RADIUS - DOT_RADIUS) as f32;
+    let (mut dx, mut dy) = (x as f32 / 32768.0, y as f32 / 32768.0);
+    let length = (dx * dx + dy * dy).sqrt();
+    if length > 1.0 {
+        dx /= length;
+        dy /= length;
+    }
+
+    (center.0 + (dx * travel).round() as i32, center.1 + (dy * travel).round() as i32)
+}
+
+/// Draws the controller of `player` as SDL maps it, with the raw joystick underneath.
+///
+/// Everything is read from the controller every frame rather than from events, so the
+/// diagram also shows what was held before the lesson started.
+fn draw_player(canvas: &mut WindowCanvas, font: &mut FontTexture, player: &Player) -> Result<(), Error> {
+    let controller = player.controller();
+
+    for (button, label, shape) in &BUTTONS {
+        let held = controller.button(*button);
+        canvas.set_draw_color(if held { HELD } else { OUTLINE });
+        let (x, y) = match *shape {
+            Shape::Circle(x, y, radius) => {
+                if held {
+                    fill_circle(canvas, x, y, radius)?;
+                } else {
+                    draw_circle(canvas, x, y, radius)?;
+                }
+                (x, y)
+            },
+            Shape::Box(x, y, width, height) => {
+                let rect = Rect::new(x, y, width, height);
+                if held {
+                    canvas.fill_rect(rect)
+                } else {
+                    canvas.draw_rect(rect)
+                }.map_err(Error::sdl(Error::Render))?;
+                (x + width as i32 / 2, y + height as i32 / 2)
+            }
+        };
+
+        // Stick labels go under the circle, out of the dot's way.
+        let (label_y, color) = match button {
+            Button::LeftStick | Button::RightStick => (y + STICK_RADIUS + 6, TEXT),
+            _ => (y - 4, if held { BACKGROUND } else { TEXT })
+        };
+        font.draw(canvas, label, x, label_y, &TextStyle::new(color).align(Align::Center))?;
+    }
+
+    for (center, x, y) in [(LEFT_STICK, Axis::LeftX, Axis::LeftY), (RIGHT_STICK, Axis::RightX, Axis::RightY)] {
+        let (dot_x, dot_y) = stick_dot(center, controller.axis(x), controller.axis(y));
+        canvas.set_draw_color(HELD);
+        fill_circle(canvas, dot_x, dot_y, DOT_RADIUS)?;
+    }
+
+    for ((x, y), axis, label) in [(LEFT_TRIGGER, Axis::TriggerLeft, "LT"), (RIGHT_TRIGGER, Axis::TriggerRight, "RT")] {
+        let value = controller.axis(axis).max(0) as u32;
+        let filled = TRIGGER_HEIGHT * value / 32767;
+
+        canvas.set_draw_color(HELD);
+        canvas.fill_rect(Rect::new(x, y + (TRIGGER_HEIGHT - filled) as i32, TRIGGER_WIDTH, filled))
+            .map_err(Error::sdl(Error::Render))?;
+        canvas.set_draw_color(OUTLINE);
+        canvas.draw_rect(Rect::new(x, y, TRIGGER_WIDTH, TRIGGER_HEIGHT)).map_err(Error::sdl(Error::Render))?;
+        font.draw(canvas, label, x + TRIGGER_WIDTH as i32 / 2, y - 12, &TextStyle::new(TEXT).align(Align::Center))?;
+    }
+
+    let mapped: Vec<String> = AXES.iter()
+        .map(|(axis, label)| format!("{} {}", label, controller.axis(*axis)))
+        .collect();
+
+    let joystick = player.joystick();
+    let raw_axes: Vec<String> = (0..joystick.num_axes())
+        .map(|axis| format!("a{} {}", axis, joystick.axis(axis).unwrap_or(0)))
+        .collect();
+    let raw_buttons: Vec<String> = (0..joystick.num_buttons())
+        .filter(|&button| joystick.button(button).unwrap_or(false))
+        .map(|button| format!("b{}", button))
+        .collect();
+    let raw_hats: Vec<String> = (0..joystick.num_hats())
+        .filter_map(|hat| joystick.hat(hat).ok().map(|state| format!("h{} {:?}", hat, state)))
+        .collect();
+
+    let lines = [
+        format!("Mapped: {}", mapped.join("  ")),
+        format!("Raw axes: {}", raw_axes.join("  ")),
+        format!("Raw buttons held: {}  Hats: {}", raw_buttons.join(" "), raw_hats.join("  ")),
+    ];
+    let style = TextStyle::new(TEXT).wrap(SCREEN_WIDTH - 16);
+    for (line, text) in lines.iter().enumerate() {
+        font.draw(canvas, text, 8, 412 + line as i32 * 14, &style)?;
+    }
+
+    Ok(())
+}
+
+/// Draws a controller with every button lit while it is held, the sticks as dots in
+/// their circles and the triggers as bars, above the raw joystick values the mapping
+/// turns into them. Tab switches between connected controllers.
+#[derive(Default)]
+pub struct GamepadVisualizer {
+    controllers: Controllers,
+    /// Index of the shown controller among the connected ones.
+    selected: usize,
+    font: Option<Font>,
+}
+
+impl GamepadVisualizer {
+    pub fn boxed() -> Box<dyn Scene> {
+        Box::<GamepadVisualizer>::default()
+    }
+}
+
+impl Scene for GamepadVisualizer {
+    fn on_enter(&mut self, app: &mut App) -> Result<(), Error> {
+        self.font = Some(Font::builtin()?);
+        self.controllers.open_all(app)
+    }
+
+    fn on_exit(&mut self, _app: &mut App) -> Result<(), Error> {
+        self.controllers.close_all();
+        Ok(())
+    }
+
+    fn on_event(&mut self, app: &mut App, event: &Event) -> Result<Transition, Error> {
+        self.controllers.handle_event(app, event)?;
+
+        if let Event::KeyDown { keycode: Some(Keycode::Tab), .. } = *event {
+            self.selected += 1;
+        }
+
+        Ok(Transition::Stay)
+    }
+
+    fn render(&mut self, canvas: &mut WindowCanvas, textures: &mut Textures) -> Result<(), Error> {
+        canvas.set_draw_color(BACKGROUND);
+        canvas.clear();
+
+        let font = match &self.font {
+            None => return Ok(()),
+            Some(font) => font
+        };
+        let mut font = textures.font(font)?;
+        let center = SCREEN_WIDTH as i32 / 2;
+
+        let count = self.controllers.len();
+        let player = match self.controllers.players().nth(self.selected % count.max(1)) {
+            None => {
+                let style = TextStyle::new(HELD).scale(2).align(Align::Center);
+                font.draw(canvas, "Connect a controller", center, 224, &style)?;
+                return Ok(());
+            },
+            Some(player) => player
+        };
+
+        let title = format!("Player {} ({} of {}): {}", player.slot() + 1, self.selected % count + 1, count, player.name());
+        font.draw(canvas, &title, center, 8, &TextStyle::new(TEXT).align(Align::Center))?;
+        font.draw(canvas, &format!("GUID {}", player.guid()), center, 22, &TextStyle::new(OUTLINE).align(Align::Center))?;
+
+        draw_player(canvas, &mut font, player)
+    }
+}
+
+pub fn run(options: &Options) -> Result<(), Error> {
+    scene::run(options, vec![GamepadVisualizer::boxed()])
+}
@@ -1,164 +1,124 @@
 use std::collections::HashMap;
 use sdl2::controller::Button;
-
 use sdl2::event::Event;
-use sdl2::hint::set;
-use sdl2::rwops::RWops;
-use sdl2::Sdl;
-use sdl2::surface::Surface;
-use sdl2::video::Window;
-
-const SCREEN_WIDTH: u32 = 640;
-const SCREEN_HEIGHT: u32 = 480;
-
-fn init() -> Result<(Sdl, Window), String> {
-    let sdl = match sdl2::init() {
-        Err(error) => {
-            let str = format!("SDL could not initialize! SDL_Error: {}", error);
-            println!("{}", str);
-            return Err(str);
-        },
-        Ok(sdl) =>  sdl
-    };
-
-    if !set("SDL_RENDER_SCALE_QUALITY", "1") {
-        println!("Warning: Linear texture filtering not enabled!")
-    }
+use sdl2::pixels::Color;
+use sdl2::render::WindowCanvas;
+use crate::Error;
+use crate::lazy_foo::app::{App, Options};
+use crate::lazy_foo::assets::Assets;
+use crate::lazy_foo::controllers::Controllers;
+use crate::lazy_foo::input::{ActionMap, Binding};
+use crate::lazy_foo::scene::{self, Scene, Textures, Transition};
+use crate::lazy_foo::text::{Font, FontTexture, TextStyle};
+
+const PRESS: &str = "imgs/gamepads_and_joysticks/press.bmp";
+
+/// One action per face button, named after it.
+fn face_buttons() -> ActionMap {
+    ActionMap::new()
+        .bind("a", Binding::Button(Button::A))
+        .bind("b", Binding::Button(Button::B))
+        .bind("x", Binding::Button(Button::X))
+        .bind("y", Binding::Button(Button::Y))
+}
 
-    let windows_builder = sdl.video().unwrap()
-        .window("SDL Tutorial", SCREEN_WIDTH, SCREEN_HEIGHT);
+/// Maps each action to the image it shows, loading every image up front so a missing
+/// file is reported before the window opens.
+fn load_media(assets: &mut Assets) -> Result<HashMap<&'static str, &'static str>, Error> {
+    let mut image_paths = HashMap::new();
+    image_paths.insert("a", "imgs/gamepads_and_joysticks/A.bmp");
+    image_paths.insert("b", "imgs/gamepads_and_joysticks/B.bmp");
+    image_paths.insert("x", "imgs/gamepads_and_joysticks/X.bmp");
+    image_paths.insert("y", "imgs/gamepads_and_joysticks/Y.bmp");
+
+    assets.surface(PRESS)?;
+    for path in image_paths.values() {
+        assets.surface(path)?;
+    }
 
-    let window = match windows_builder.build() {
-        Err(error) => {
-            let str = format!("Window could not be created! SDL_Error: %{}", error);
-            println!("{}", str);
-            return Err(str);
-        },
-        Ok(window) => window
-    };
+    Ok(image_paths)
+}
 
-    Ok((sdl, window))
+/// Draws `text` with a dark shadow so it can be read on any image.
+fn draw_label(canvas: &mut WindowCanvas, font: &mut FontTexture, text: &str, x: i32, y: i32) -> Result<(), Error> {
+    font.draw(canvas, text, x + 2, y + 2, &TextStyle::new(Color::BLACK).scale(2))?;
+    font.draw(canvas, text, x, y, &TextStyle::new(Color::YELLOW).scale(2))?;
+    Ok(())
 }
 
-fn load_media() -> Option<HashMap<i32, Surface<'static>>> {
-    let mut surfaces = HashMap::new();
-    surfaces.insert(-2, match load_surface("imgs/gamepads_and_joysticks/press.bmp") {
-        None => {
-            println!("Failed to load default image!");
-            return None;
-        },
-        Some(surface) => surface
-    });
-    surfaces.insert(Button::A as i32, match load_surface("imgs/gamepads_and_joysticks/A.bmp") {
-        None => {
-            println!("Failed to load up image!");
-            return None;
-        },
-        Some(surface) => surface
-    });
-    surfaces.insert(Button::B as i32, match load_surface("imgs/gamepads_and_joysticks/B.bmp") {
-        None => {
-            println!("Failed to load down image!");
-            return None;
-        },
-        Some(surface) => surface
-    });
-    surfaces.insert(Button::X as i32, match load_surface("imgs/gamepads_and_joysticks/X.bmp") {
-        None => {
-            println!("Failed to load left image!");
-            return None;
-        },
-        Some(surface) => surface
-    });
-    surfaces.insert(Button::Y as i32, match load_surface("imgs/gamepads_and_joysticks/Y.bmp") {
-        None => {
-            println!("Failed to load right image!");
-            return None;
-        },
-        Some(surface) => surface
-    });
-
-    Some(surfaces)
+#[derive(Default)]
+pub struct GamepadsAndJoysticks {
+    actions: ActionMap,
+    image_paths: HashMap<&'static str, &'static str>,
+    current_screen: &'static str,
+    controllers: Controllers,
+    font: Option<Font>,
 }
 
-fn load_surface(path: &str) -> Option<Surface> {
-    match Surface::load_bmp(path) {
-        Err(error) => {
-            println!("Unable to load image {}! SDL Error: {}", path, error);
-            None
-        },
-        Ok(surface) => Some(surface)
+impl GamepadsAndJoysticks {
+    pub fn boxed() -> Box<dyn Scene> {
+        Box::<GamepadsAndJoysticks>::default()
     }
 }
 
-pub fn run() {
-    let (sdl, window) = match init() {
-        Err(_) => {
-            println!("Failed to initialize!");
-            return;
-        }
-        Ok(tuple) => tuple
-    };
-
-    let key_press_surfaces = match load_media() {
-        None => {
-            println!("Failed to load media!");
-            return;
-        },
-        Some(surfaces) => surfaces
-    };
-
-    let mut current_screen = key_press_surfaces.get(&-2).unwrap();
-
-    let joystick = match sdl.game_controller() {
-        Err(error) => {
-            let str = format!("SDL could not initialize! SDL Error: {}", error);
-            println!("{}", str);
-            return;
-        },
-        Ok(joystick) => joystick
-    };
-
-    let rw = RWops::from_file("controller_mapping.txt", "rb").unwrap();
-    joystick.load_mappings_from_rw(rw).unwrap();
-
-    let _game_controller = joystick.open(0).unwrap();
-
-    if joystick.num_joysticks().unwrap() < 1 {
-        println!("Warning: No joysticks connected!");
-        return;
-    }
+impl Scene for GamepadsAndJoysticks {
+    fn on_enter(&mut self, app: &mut App) -> Result<(), Error> {
+        self.actions = face_buttons();
+        self.image_paths = load_media(app.assets())?;
+        self.current_screen = PRESS;
+        self.font = Some(Font::builtin()?);
 
-    let mut event_pump = sdl.event_pump().unwrap();
+        self.controllers.open_all(app)
+    }
 
-    'running: loop {
-        let event = match event_pump.poll_event() {
-            Some(event) => event,
-            None => continue
-        };
+    fn on_exit(&mut self, _app: &mut App) -> Result<(), Error> {
+        self.controllers.close_all();
+        Ok(())
+    }
 
-        if let Event::Quit {..} = event {
-            break 'running;
-        }
+    fn on_event(&mut self, app: &mut App, event: &Event) -> Result<Transition, Error> {
+        self.controllers.handle_event(app, event)?;
 
-        match event {
-            Event::ControllerButtonDown { button, .. } => {
-                match button {
-                    Button::A
-                    | Button::B
-                    | Button::X
-                    | Button::Y  => {
-                        current_screen = key_press_surfaces.get(&(button as i32)).unwrap();
-                    },
-                    _ => current_screen = key_press_surfaces.get(&-2).unwrap()
-                };
+        match *event {
+            Event::ControllerButtonDown {..} => {
+                self.current_screen = self.actions.triggered_by(event)
+                    .and_then(|action| self.image_paths.get(action))
+                    .copied()
+                    .unwrap_or(PRESS);
             },
-            Event::KeyDown {..} => current_screen = key_press_surfaces.get(&-2).unwrap(),
+            Event::KeyDown {..} => self.current_screen = PRESS,
             _ => ()
         }
 
-        let mut screen_surface = window.surface(&event_pump).unwrap();
-        current_screen.blit(None, &mut screen_surface, None).expect("");
-        screen_surface.update_window().unwrap();
+        Ok(Transition::Stay)
     }
-}
\ No newline at end of file
+
+    fn render(&mut self, canvas: &mut WindowCanvas, textures: &mut Textures) -> Result<(), Error> {
+        let current_texture = textures.texture(self.current_screen)?;
+        canvas.copy(&current_texture, None, None).map_err(Error::sdl(Error::Render))?;
+
+        let font = match &self.font {
+            None => return Ok(()),
+            Some(font) => font
+        };
+        let mut font = textures.font(font)?;
+        if self.controllers.is_empty() {
+            // Where the first player's line goes once a controller is plugged in.
+            draw_label(canvas, &mut font, "Connect a controller", 8, 8)?;
+        }
+        for (line, player) in self.controllers.players().enumerate() {
+            let pressed = match player.last_button() {
+                None => "-".to_string(),
+                Some(button) => button.string().to_uppercase()
+            };
+            let label = format!("Player {}: {} ({})", player.slot() + 1, pressed, player.name());
+            draw_label(canvas, &mut font, &label, 8, 8 + line as i32 * 24)?;
+        }
+
+        Ok(())
+    }
+}
+
+pub fn run(options: &Options) -> Result<(), Error> {
+    scene::run(options, vec![GamepadsAndJoysticks::boxed()])
+}
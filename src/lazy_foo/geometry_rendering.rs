@@ -1,73 +1,26 @@
 use sdl2::event::Event;
-use sdl2::hint::set;
 use sdl2::pixels::Color;
 use sdl2::rect::Rect;
-use sdl2::render::{CanvasBuilder, WindowCanvas};
-use sdl2::Sdl;
+use sdl2::render::WindowCanvas;
+use crate::Error;
+use crate::lazy_foo::app::{App, Options, SCREEN_HEIGHT, SCREEN_WIDTH};
+use crate::lazy_foo::scene::{self, Scene, Textures, Transition};
 
-const SCREEN_WIDTH: u32 = 640;
-const SCREEN_HEIGHT: u32 = 480;
 
-fn init() -> Result<(Sdl, WindowCanvas), String> {
-    let sdl = match sdl2::init() {
-        Err(error) => {
-            let str = format!("SDL could not initialize! SDL_Error: {}", error);
-            println!("{}", str);
-            return Err(str);
-        },
-        Ok(sdl) =>  sdl
-    };
+pub struct GeometryRendering;
 
-    if !set("SDL_RENDER_SCALE_QUALITY", "1") {
-        println!("Warning: Linear texture filtering not enabled!")
+impl GeometryRendering {
+    pub fn boxed() -> Box<dyn Scene> {
+        Box::new(GeometryRendering)
     }
-
-    let windows_builder = sdl.video().unwrap()
-        .window("SDL Tutorial", SCREEN_WIDTH, SCREEN_HEIGHT);
-
-    let window = match windows_builder.build() {
-        Err(error) => {
-            let str = format!("Window could not be created! SDL_Error: %{}", error);
-            println!("{}", str);
-            return Err(str);
-        },
-        Ok(window) => window
-    };
-
-    let renderer = match CanvasBuilder::new(window).accelerated().build() {
-        Err(error) => {
-            let str = format!("Renderer could not be created! SDL Error: {}", error);
-            println!("{}", str);
-            return Err(str);
-        },
-        Ok(canvas) => canvas
-    };
-
-    Ok((sdl, renderer))
 }
 
-pub fn run() {
-    // Necessary to keep SDL2 Image Context alive
-    let (sdl, mut renderer) = match init() {
-        Err(_) => {
-            println!("Failed to initialize!");
-            return;
-        }
-        Ok(tuple) => tuple
-    };
-
-    let mut event_pump = sdl.event_pump().unwrap();
-
-    'running: loop {
-        let event = match event_pump.poll_event() {
-            Some(event) => event,
-            None => continue
-        };
-
-        if let Event::Quit {..} = event {
-            break 'running;
-        }
+impl Scene for GeometryRendering {
+    fn on_event(&mut self, _app: &mut App, _event: &Event) -> Result<Transition, Error> {
+        Ok(Transition::Stay)
+    }
 
+    fn render(&mut self, renderer: &mut WindowCanvas, _textures: &mut Textures) -> Result<(), Error> {
         renderer.set_draw_color(Color::WHITE);
         renderer.clear();
 
@@ -78,7 +31,7 @@ pub fn run() {
             SCREEN_HEIGHT / 2
         );
         renderer.set_draw_color(Color::RED);
-        renderer.fill_rect(fill_rect).unwrap();
+        renderer.fill_rect(fill_rect).map_err(Error::sdl(Error::Render))?;
 
         let outline_rect = Rect::new(
             (SCREEN_WIDTH / 6) as i32,
@@ -87,19 +40,23 @@ pub fn run() {
             SCREEN_HEIGHT * 2 / 3
         );
         renderer.set_draw_color(Color::GREEN);
-        renderer.draw_rect(outline_rect).unwrap();
+        renderer.draw_rect(outline_rect).map_err(Error::sdl(Error::Render))?;
 
         renderer.set_draw_color(Color::BLUE);
         renderer.draw_line((0, (SCREEN_HEIGHT / 2) as i32),
                            (SCREEN_WIDTH as i32, (SCREEN_HEIGHT / 2) as i32))
-            .unwrap();
+            .map_err(Error::sdl(Error::Render))?;
 
         renderer.set_draw_color(Color::YELLOW);
         for i in (0..SCREEN_HEIGHT).step_by(4) {
             let point = ((SCREEN_WIDTH/2) as i32, i as i32);
-            renderer.draw_point(point).unwrap();
+            renderer.draw_point(point).map_err(Error::sdl(Error::Render))?;
         }
 
-        renderer.present();
+        Ok(())
     }
-}
\ No newline at end of file
+}
+
+pub fn run(options: &Options) -> Result<(), Error> {
+    scene::run(options, vec![GeometryRendering::boxed()])
+}
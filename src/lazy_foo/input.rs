@@ -0,0 +1,526 @@
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use sdl2::controller::{Axis, Button};
+use sdl2::event::{Event, WindowEvent};
+use sdl2::keyboard::{Keycode, Scancode};
+use crate::Error;
+
+pub const UP: &str = "up";
+pub const DOWN: &str = "down";
+pub const LEFT: &str = "left";
+pub const RIGHT: &str = "right";
+pub const CONFIRM: &str = "confirm";
+pub const BACK: &str = "back";
+
+/// How far a stick has to be pushed for its direction to count as pressed.
+pub const AXIS_THRESHOLD: i16 = 8000;
+
+/// Bindings of [`ActionMap::standard`].
+pub const DEFAULT_BINDINGS: &str = r#"
+up = ["key:Up", "button:dpup", "axis:lefty-"]
+down = ["key:Down", "button:dpdown", "axis:lefty+"]
+left = ["key:Left", "button:dpleft", "axis:leftx-"]
+right = ["key:Right", "button:dpright", "axis:leftx+"]
+confirm = ["key:Return", "key:Space", "button:a"]
+back = ["key:Backspace", "button:b"]
+"#;
+
+/// Where the user's settings are kept: `$XDG_CONFIG_HOME/sdl2_learn`, or
+/// `~/.config/sdl2_learn` when `XDG_CONFIG_HOME` is not set.
+pub fn user_config_dir() -> Option<PathBuf> {
+    let config = match env::var_os("XDG_CONFIG_HOME").filter(|dir| !dir.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(env::var_os("HOME")?).join(".config")
+    };
+
+    Some(config.join("sdl2_learn"))
+}
+
+/// Where the user's own bindings are kept, `bindings.toml` in [`user_config_dir`].
+pub fn user_bindings_path() -> Option<PathBuf> {
+    Some(user_config_dir()?.join("bindings.toml"))
+}
+
+/// Why a bindings file could not be loaded or saved.
+#[derive(Debug)]
+pub enum BindingsError {
+    Io(io::Error),
+    Parse { line: usize, message: String },
+}
+
+impl fmt::Display for BindingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BindingsError::Io(error) => write!(f, "{}", error),
+            BindingsError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl error::Error for BindingsError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            BindingsError::Io(error) => Some(error),
+            BindingsError::Parse { .. } => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AxisDirection {
+    Negative,
+    Positive,
+}
+
+/// One input that can press an action.
+///
+/// Written in bindings files as `key:<SDL key name>`, `scancode:<SDL scancode name>`,
+/// `button:<button>` or `axis:<axis>+`/`axis:<axis>-`, buttons and axes using the
+/// GameControllerDB names (`a`, `dpup`, `leftx`...).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Binding {
+    Key(Keycode),
+    Scancode(Scancode),
+    Button(Button),
+    Axis(Axis, AxisDirection),
+}
+
+impl Binding {
+    pub fn parse(text: &str) -> Result<Binding, String> {
+        let (kind, name) = text.split_once(':')
+            .ok_or(format!("'{}' is not <kind>:<name>", text))?;
+
+        match kind {
+            "key" => Keycode::from_name(name)
+                .map(Binding::Key)
+                .ok_or(format!("unknown key '{}'", name)),
+            "scancode" => Scancode::from_name(name)
+                .map(Binding::Scancode)
+                .ok_or(format!("unknown scancode '{}'", name)),
+            "button" => Button::from_string(name)
+                .map(Binding::Button)
+                .ok_or(format!("unknown controller button '{}'", name)),
+            "axis" => {
+                let (axis, direction) = match name.strip_suffix('+') {
+                    Some(axis) => (axis, AxisDirection::Positive),
+                    None => match name.strip_suffix('-') {
+                        None => return Err(format!("axis '{}' needs a direction, + or -", name)),
+                        Some(axis) => (axis, AxisDirection::Negative)
+                    }
+                };
+                Axis::from_string(axis)
+                    .map(|axis| Binding::Axis(axis, direction))
+                    .ok_or(format!("unknown controller axis '{}'", axis))
+            },
+            _ => Err(format!("unknown binding kind '{}'", kind))
+        }
+    }
+
+    /// The binding `event` presses, e.g. to capture a new one; sticks only count past
+    /// [`AXIS_THRESHOLD`].
+    pub fn from_event(event: &Event) -> Option<Binding> {
+        match *event {
+            Event::KeyDown { keycode: Some(keycode), .. } => Some(Binding::Key(keycode)),
+            Event::ControllerButtonDown { button, .. } => Some(Binding::Button(button)),
+            Event::ControllerAxisMotion { axis, value, .. } if value >= AXIS_THRESHOLD => {
+                Some(Binding::Axis(axis, AxisDirection::Positive))
+            },
+            Event::ControllerAxisMotion { axis, value, .. } if value <= -AXIS_THRESHOLD => {
+                Some(Binding::Axis(axis, AxisDirection::Negative))
+            },
+            _ => None
+        }
+    }
+}
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Binding::Key(keycode) => write!(f, "key:{}", keycode.name()),
+            Binding::Scancode(scancode) => write!(f, "scancode:{}", scancode.name()),
+            Binding::Button(button) => write!(f, "button:{}", button.string()),
+            Binding::Axis(axis, AxisDirection::Negative) => write!(f, "axis:{}-", axis.string()),
+            Binding::Axis(axis, AxisDirection::Positive) => write!(f, "axis:{}+", axis.string()),
+        }
+    }
+}
+
+/// A named action and everything bound to it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Action {
+    pub name: String,
+    pub bindings: Vec<Binding>,
+}
+
+/// Parses the quoted strings of a `["...", "..."]` list.
+fn parse_list(text: &str) -> Result<Vec<String>, String> {
+    let inner = text.strip_prefix('[')
+        .and_then(|text| text.strip_suffix(']'))
+        .ok_or(format!("'{}' is not a list of bindings", text))?;
+
+    let mut items = Vec::new();
+    let mut chars = inner.chars().peekable();
+    loop {
+        while chars.next_if(|&c| c.is_whitespace()).is_some() {}
+        match chars.next() {
+            None => break,
+            Some('"') => (),
+            Some(c) => return Err(format!("expected '\"', found '{}'", c))
+        }
+
+        let mut item = String::new();
+        loop {
+            match chars.next() {
+                None => return Err("unterminated string".to_string()),
+                Some('"') => break,
+                Some('\\') => match chars.next() {
+                    Some(c @ ('"' | '\\')) => item.push(c),
+                    _ => return Err("unsupported escape in string".to_string())
+                },
+                Some(c) => item.push(c)
+            }
+        }
+        items.push(item);
+
+        while chars.next_if(|&c| c.is_whitespace()).is_some() {}
+        match chars.next() {
+            None => break,
+            Some(',') => (),
+            Some(c) => return Err(format!("expected ',' or ']', found '{}'", c))
+        }
+    }
+
+    Ok(items)
+}
+
+/// `line` up to the `#` starting its comment, if any; a `#` inside a quoted binding
+/// such as `"key:#"` is part of the binding.
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    let mut escaped = false;
+    for (index, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            '#' if !quoted => return &line[..index],
+            _ => ()
+        }
+    }
+
+    line
+}
+
+fn parse_line(line: &str) -> Result<Action, String> {
+    let (name, list) = line.split_once('=').ok_or("expected <action> = [<bindings>]")?;
+    let name = name.trim();
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+        return Err(format!("'{}' is not an action name", name));
+    }
+
+    let bindings = parse_list(list.trim())?
+        .iter()
+        .map(|binding| Binding::parse(binding))
+        .collect::<Result<_, _>>()?;
+
+    Ok(Action { name: name.to_string(), bindings })
+}
+
+/// Named actions (up, confirm, ...) bound to keys, controller buttons and stick directions,
+/// with the state of each action this frame.
+///
+/// Bindings files are a small subset of TOML, one action per line, `#` outside quotes
+/// starting a comment:
+///
+/// ```text
+/// # action = [bindings]
+/// up = ["key:Up", "scancode:W", "button:dpup", "axis:lefty-"]
+/// ```
+///
+/// Feed it every event with [`ActionMap::handle_event`] and call [`ActionMap::begin_frame`]
+/// before each frame's events; [`App`](crate::lazy_foo::app::App) does both for its map.
+#[derive(Debug, Clone, Default)]
+pub struct ActionMap {
+    actions: Vec<Action>,
+    keys: HashSet<Keycode>,
+    scancodes: HashSet<Scancode>,
+    /// Buttons held, by joystick instance id.
+    buttons: HashSet<(u32, Button)>,
+    /// Last value of each axis, by joystick instance id.
+    axes: HashMap<(u32, Axis), i16>,
+    pressed: Vec<bool>,
+    just_pressed: HashSet<usize>,
+    just_released: HashSet<usize>,
+}
+
+impl ActionMap {
+    /// A map without any action.
+    pub fn new() -> ActionMap {
+        ActionMap::default()
+    }
+
+    /// The up/down/left/right/confirm/back actions of [`DEFAULT_BINDINGS`].
+    pub fn standard() -> ActionMap {
+        ActionMap::parse(DEFAULT_BINDINGS).expect("default bindings are valid")
+    }
+
+    pub fn parse(text: &str) -> Result<ActionMap, BindingsError> {
+        let mut map = ActionMap::new();
+
+        for (index, line) in text.lines().enumerate() {
+            let line = strip_comment(line);
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let action = parse_line(line)
+                .map_err(|message| BindingsError::Parse { line: index + 1, message })?;
+            map.set(&action.name, action.bindings);
+        }
+
+        Ok(map)
+    }
+
+    pub fn load(path: &Path) -> Result<ActionMap, Error> {
+        let bindings_error = |source| Error::Bindings { path: path.display().to_string(), source };
+
+        let text = fs::read_to_string(path).map_err(|error| bindings_error(BindingsError::Io(error)))?;
+        ActionMap::parse(&text).map_err(bindings_error)
+    }
+
+    /// Writes the map in the format [`ActionMap::parse`] reads.
+    pub fn to_config(&self) -> String {
+        let mut text = String::new();
+        for action in &self.actions {
+            let bindings: Vec<String> = action.bindings.iter()
+                .map(|binding| format!("\"{}\"", binding.to_string().replace('\\', "\\\\").replace('"', "\\\"")))
+                .collect();
+            text.push_str(&format!("{} = [{}]\n", action.name, bindings.join(", ")));
+        }
+        text
+    }
+
+    /// Saves the map to `path`, creating its directory if needed.
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        let bindings_error = |error| Error::Bindings {
+            path: path.display().to_string(),
+            source: BindingsError::Io(error),
+        };
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(bindings_error)?;
+        }
+        fs::write(path, self.to_config()).map_err(bindings_error)
+    }
+
+    /// Adds `binding` to `action`, creating the action if needed.
+    pub fn bind(mut self, action: &str, binding: Binding) -> ActionMap {
+        let mut bindings = self.bindings(action).to_vec();
+        bindings.push(binding);
+        self.set(action, bindings);
+        self
+    }
+
+    /// Replaces everything bound to `action`, creating the action if needed.
+    pub fn set(&mut self, action: &str, bindings: Vec<Binding>) {
+        match self.index(action) {
+            Some(index) => self.actions[index].bindings = bindings,
+            None => {
+                self.actions.push(Action { name: action.to_string(), bindings });
+                self.pressed.push(false);
+            }
+        }
+        self.refresh();
+    }
+
+    /// Replaces this map's actions with those of `other`, keeping the ones it does not have.
+    pub fn extend(&mut self, other: ActionMap) {
+        for action in other.actions {
+            self.set(&action.name, action.bindings);
+        }
+    }
+
+    /// Removes `binding` from every action it is bound to.
+    pub fn unbind(&mut self, binding: Binding) {
+        for action in &mut self.actions {
+            action.bindings.retain(|&bound| bound != binding);
+        }
+        self.refresh();
+    }
+
+    /// Puts back the standard bindings of `action`, or none if it is not a standard action.
+    pub fn reset(&mut self, action: &str) {
+        let standard = ActionMap::standard();
+        self.set(action, standard.bindings(action).to_vec());
+    }
+
+    /// Puts back the standard bindings of every action.
+    pub fn reset_all(&mut self) {
+        let names: Vec<String> = self.actions.iter().map(|action| action.name.clone()).collect();
+        for name in names {
+            self.reset(&name);
+        }
+    }
+
+    /// Every action `binding` is bound to, e.g. to warn before binding it to another one.
+    pub fn bound_to(&self, binding: Binding) -> Vec<&str> {
+        self.actions.iter()
+            .filter(|action| action.bindings.contains(&binding))
+            .map(|action| action.name.as_str())
+            .collect()
+    }
+
+    pub fn actions(&self) -> &[Action] {
+        &self.actions
+    }
+
+    pub fn bindings(&self, action: &str) -> &[Binding] {
+        match self.index(action) {
+            None => &[],
+            Some(index) => &self.actions[index].bindings
+        }
+    }
+
+    fn index(&self, action: &str) -> Option<usize> {
+        self.actions.iter().position(|known| known.name == action)
+    }
+
+    /// Forgets which actions were just pressed or released; call it before each frame's events.
+    pub fn begin_frame(&mut self) {
+        self.just_pressed.clear();
+        self.just_released.clear();
+    }
+
+    pub fn handle_event(&mut self, event: &Event) {
+        match *event {
+            Event::KeyDown { keycode, scancode, .. } => {
+                self.keys.extend(keycode);
+                self.scancodes.extend(scancode);
+            },
+            Event::KeyUp { keycode, scancode, .. } => {
+                if let Some(keycode) = keycode {
+                    self.keys.remove(&keycode);
+                }
+                if let Some(scancode) = scancode {
+                    self.scancodes.remove(&scancode);
+                }
+            },
+            Event::ControllerButtonDown { which, button, .. } => {
+                self.buttons.insert((which, button));
+            },
+            Event::ControllerButtonUp { which, button, .. } => {
+                self.buttons.remove(&(which, button));
+            },
+            Event::ControllerAxisMotion { which, axis, value, .. } => {
+                self.axes.insert((which, axis), value);
+            },
+            Event::ControllerDeviceRemoved { which, .. } => {
+                self.buttons.retain(|&(id, _)| id != which);
+                self.axes.retain(|&(id, _), _| id != which);
+            },
+            // Keys held while the window lost focus never get their key up.
+            Event::Window { win_event: WindowEvent::FocusLost, .. } => {
+                self.keys.clear();
+                self.scancodes.clear();
+            },
+            _ => return
+        }
+
+        self.refresh();
+    }
+
+    /// Recomputes which actions are pressed, noting the ones that changed.
+    fn refresh(&mut self) {
+        for index in 0..self.actions.len() {
+            let pressed = self.actions[index].bindings.iter().any(|&binding| self.value(binding) > 0.0);
+            match (self.pressed[index], pressed) {
+                (false, true) => {
+                    self.just_pressed.insert(index);
+                },
+                (true, false) => {
+                    self.just_released.insert(index);
+                },
+                _ => ()
+            }
+            self.pressed[index] = pressed;
+        }
+    }
+
+    /// How far `binding` is pressed, from 0.0 to 1.0; sticks only count past [`AXIS_THRESHOLD`].
+    fn value(&self, binding: Binding) -> f32 {
+        let held = |held: bool| if held { 1.0 } else { 0.0 };
+
+        match binding {
+            Binding::Key(keycode) => held(self.keys.contains(&keycode)),
+            Binding::Scancode(scancode) => held(self.scancodes.contains(&scancode)),
+            Binding::Button(button) => held(self.buttons.iter().any(|&(_, held)| held == button)),
+            Binding::Axis(axis, direction) => {
+                self.axes.iter()
+                    .filter(|&(&(_, moved), _)| moved == axis)
+                    .map(|(_, &value)| {
+                        let value = match direction {
+                            AxisDirection::Positive => value as i32,
+                            AxisDirection::Negative => -(value as i32),
+                        };
+                        if value < AXIS_THRESHOLD as i32 { 0.0 } else { (value as f32 / 32767.0).min(1.0) }
+                    })
+                    .fold(0.0, f32::max)
+            }
+        }
+    }
+
+    /// Whether anything bound to `action` is held.
+    pub fn pressed(&self, action: &str) -> bool {
+        self.index(action).is_some_and(|index| self.pressed[index])
+    }
+
+    /// Whether `action` went from released to pressed during this frame.
+    pub fn just_pressed(&self, action: &str) -> bool {
+        self.index(action).is_some_and(|index| self.just_pressed.contains(&index))
+    }
+
+    /// Whether `action` went from pressed to released during this frame.
+    pub fn just_released(&self, action: &str) -> bool {
+        self.index(action).is_some_and(|index| self.just_released.contains(&index))
+    }
+
+    /// How far `action` is pressed, from 0.0 to 1.0: 1.0 for keys and buttons, the stick's
+    /// position for axes.
+    pub fn axis_value(&self, action: &str) -> f32 {
+        self.bindings(action).iter()
+            .map(|&binding| self.value(binding))
+            .fold(0.0, f32::max)
+    }
+
+    /// `positive`'s value minus `negative`'s, e.g. `axis(LEFT, RIGHT)` from -1.0 to 1.0.
+    pub fn axis(&self, negative: &str, positive: &str) -> f32 {
+        self.axis_value(positive) - self.axis_value(negative)
+    }
+
+    /// The first action `event` presses, if any.
+    ///
+    /// Stick motion counts every time it is reported past the threshold.
+    pub fn triggered_by(&self, event: &Event) -> Option<&str> {
+        let matches = |binding: &Binding| match (*binding, event) {
+            (Binding::Key(bound), Event::KeyDown { keycode: Some(keycode), .. }) => bound == *keycode,
+            (Binding::Scancode(bound), Event::KeyDown { scancode: Some(scancode), .. }) => bound == *scancode,
+            (Binding::Button(bound), Event::ControllerButtonDown { button, .. }) => bound == *button,
+            (Binding::Axis(bound, direction), Event::ControllerAxisMotion { axis, value, .. }) => {
+                bound == *axis && match direction {
+                    AxisDirection::Positive => *value >= AXIS_THRESHOLD,
+                    AxisDirection::Negative => *value <= -AXIS_THRESHOLD,
+                }
+            },
+            _ => false
+        };
+
+        self.actions.iter()
+            .find(|action| action.bindings.iter().any(matches))
+            .map(|action| action.name.as_str())
+    }
+}
@@ -1,132 +1,69 @@
 use std::collections::HashMap;
 use sdl2::event::Event;
-use sdl2::keyboard::Keycode;
-use sdl2::Sdl;
-use sdl2::surface::Surface;
-use sdl2::video::Window;
-
-
-fn init() -> Option<(Sdl, Window)> {
-    const SCREEN_WIDTH: u32 = 640;
-    const SCREEN_HEIGHT: u32 = 480;
-
-    let sdl_ctx = match sdl2::init() {
-        Err(error) => {
-            println!("SDL could not initialize! SDL_Error: {}", error);
-            return None;
-        },
-        Ok(sdl) =>  sdl
-    };
-
-    let windows_builder = sdl_ctx.video().unwrap()
-        .window("SDL Tutorial", SCREEN_WIDTH, SCREEN_HEIGHT);
-
-    let window = match windows_builder.build() {
-        Err(error) => {
-            println!("Window could not be created! SDL_Error: %{}", error);
-            return None;
-        },
-        Ok(window) => window
-    };
+use sdl2::render::WindowCanvas;
+use crate::Error;
+use crate::lazy_foo::app::{App, Options};
+use crate::lazy_foo::assets::Assets;
+use crate::lazy_foo::input;
+use crate::lazy_foo::scene::{self, Scene, Textures, Transition};
+
+
+const PRESS: &str = "imgs/key_presses/press.bmp";
+
+/// Maps each action to the image it shows, loading every image up front so a missing
+/// file is reported before the window opens.
+fn load_media(assets: &mut Assets) -> Result<HashMap<&'static str, &'static str>, Error> {
+    let mut image_paths = HashMap::new();
+    image_paths.insert(input::UP, "imgs/key_presses/up.bmp");
+    image_paths.insert(input::DOWN, "imgs/key_presses/down.bmp");
+    image_paths.insert(input::LEFT, "imgs/key_presses/left.bmp");
+    image_paths.insert(input::RIGHT, "imgs/key_presses/right.bmp");
+
+    assets.surface(PRESS)?;
+    for path in image_paths.values() {
+        assets.surface(path)?;
+    }
 
-    Some((sdl_ctx, window))
+    Ok(image_paths)
 }
 
-fn load_media() -> Option<HashMap<i32, Surface<'static>>> {
-    let mut surfaces = HashMap::new();
-    surfaces.insert(0, match load_surface("imgs/key_presses/press.bmp") {
-        None => {
-            println!("Failed to load default image!");
-            return None;
-        },
-        Some(surface) => surface
-    });
-    surfaces.insert(Keycode::Up as i32, match load_surface("imgs/key_presses/up.bmp") {
-        None => {
-            println!("Failed to load up image!");
-            return None;
-        },
-        Some(surface) => surface
-    });
-    surfaces.insert(Keycode::Down as i32, match load_surface("imgs/key_presses/down.bmp") {
-        None => {
-            println!("Failed to load down image!");
-            return None;
-        },
-        Some(surface) => surface
-    });
-    surfaces.insert(Keycode::Left as i32, match load_surface("imgs/key_presses/left.bmp") {
-        None => {
-            println!("Failed to load left image!");
-            return None;
-        },
-        Some(surface) => surface
-    });
-    surfaces.insert(Keycode::Right as i32, match load_surface("imgs/key_presses/right.bmp") {
-        None => {
-            println!("Failed to load right image!");
-            return None;
-        },
-        Some(surface) => surface
-    });
-
-    Some(surfaces)
+#[derive(Default)]
+pub struct KeyPresses {
+    image_paths: HashMap<&'static str, &'static str>,
+    current_screen: &'static str,
 }
 
-fn load_surface(path: &str) -> Option<Surface> {
-    match Surface::load_bmp(path) {
-        Err(error) => {
-            println!("Unable to load image {}! SDL Error: {}", path, error);
-            None
-        },
-        Ok(surface) => Some(surface)
+impl KeyPresses {
+    pub fn boxed() -> Box<dyn Scene> {
+        Box::<KeyPresses>::default()
     }
 }
 
-pub fn run() {
-    let (sdl_ctx, window) = match init() {
-        None => {
-            println!("Failed to initialize!");
-            return
-        },
-        Some(tuple) => tuple
-    };
-
-    let mut event_pump = sdl_ctx.event_pump().unwrap();
-
-    let key_press_surfaces = match load_media() {
-        None => {
-            println!("Failed to load media!");
-            return;
-        },
-        Some(surfaces) => surfaces
-    };
-
-    let mut current_screen = &key_press_surfaces[&0];
-
-    'running: loop {
-        let event = match event_pump.poll_event() {
-            Some(event) => event,
-            None => continue
-        };
+impl Scene for KeyPresses {
+    fn on_enter(&mut self, app: &mut App) -> Result<(), Error> {
+        self.image_paths = load_media(app.assets())?;
+        self.current_screen = PRESS;
+        Ok(())
+    }
 
-        if let Event::Quit {..} = event {
-            break 'running;
+    fn on_event(&mut self, app: &mut App, event: &Event) -> Result<Transition, Error> {
+        if let Event::KeyDown {..} = *event {
+            // Keys bound to no action, or to one without an image, show the default image.
+            self.current_screen = app.actions().triggered_by(event)
+                .and_then(|action| self.image_paths.get(action))
+                .copied()
+                .unwrap_or(PRESS);
         }
 
-        if let Event::KeyDown {keycode, ..} = event {
-            let keycode = keycode.unwrap();
-            match keycode {
-                Keycode::Up | Keycode::Down
-                | Keycode::Left | Keycode::Right => {
-                    current_screen = &key_press_surfaces[&(keycode as i32)]
-                },
-                _ => current_screen = &key_press_surfaces[&0]
-            };
-        }
+        Ok(Transition::Stay)
+    }
 
-        let mut screen_surface = window.surface(&event_pump).unwrap();
-        current_screen.blit(None, &mut screen_surface, None).expect("");
-        screen_surface.update_window().unwrap();
+    fn render(&mut self, canvas: &mut WindowCanvas, textures: &mut Textures) -> Result<(), Error> {
+        let current_texture = textures.texture(self.current_screen)?;
+        canvas.copy(&current_texture, None, None).map_err(Error::sdl(Error::Render))
     }
-}
\ No newline at end of file
+}
+
+pub fn run(options: &Options) -> Result<(), Error> {
+    scene::run(options, vec![KeyPresses::boxed()])
+}
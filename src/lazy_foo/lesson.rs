@@ -0,0 +1,50 @@
+use crate::Error;
+use crate::lazy_foo::app::{App, Options};
+use crate::lazy_foo::scene::SceneFn;
+
+/// Entry point of a lesson.
+pub type LessonFn = fn(&Options) -> Result<(), Error>;
+
+/// Lesson drawing on the window surface of an app it is given, built without a renderer.
+pub type SurfaceFn = fn(&mut App) -> Result<(), Error>;
+
+/// A runnable lesson, as listed by the launcher.
+#[derive(Clone, Copy)]
+pub struct Lesson {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub run: LessonFn,
+    /// The lesson as a scene, for lessons that can share a window with others.
+    pub scene: Option<SceneFn>,
+    /// The lesson on a window surface, for lessons that need a window of their own but can
+    /// still be started from the menu.
+    pub surface: Option<SurfaceFn>,
+}
+
+impl Lesson {
+    pub const fn new(name: &'static str, description: &'static str, run: LessonFn) -> Lesson {
+        Lesson { name, description, run, scene: None, surface: None }
+    }
+
+    pub const fn scene(mut self, scene: SceneFn) -> Lesson {
+        self.scene = Some(scene);
+        self
+    }
+
+    pub const fn surface(mut self, surface: SurfaceFn) -> Lesson {
+        self.surface = Some(surface);
+        self
+    }
+}
+
+/// Looks a lesson up either by its name or by its position in `lessons`.
+pub fn find<'a>(lessons: &'a [Lesson], selector: &str) -> Option<&'a Lesson> {
+    if let Some(lesson) = lessons.iter().find(|lesson| lesson.name == selector) {
+        return Some(lesson);
+    }
+
+    match selector.parse::<usize>() {
+        Err(_) => None,
+        Ok(index) => lessons.get(index)
+    }
+}
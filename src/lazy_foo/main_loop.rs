@@ -0,0 +1,163 @@
+use std::collections::VecDeque;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Number of recent frames the statistics are computed over.
+const STATS_WINDOW: usize = 120;
+
+/// Pacing settings of a [`MainLoop`].
+#[derive(Debug, Clone)]
+pub struct LoopConfig {
+    /// Fixed updates per second.
+    pub tick_rate: u32,
+    /// Frames per second to pace presenting to, or `None` to rely on vsync (or run flat out).
+    pub frame_cap: Option<u32>,
+    /// Most updates run in a single frame, so a long stall does not snowball.
+    pub max_ticks_per_frame: u32,
+}
+
+impl Default for LoopConfig {
+    fn default() -> LoopConfig {
+        LoopConfig { tick_rate: 60, frame_cap: Some(60), max_ticks_per_frame: 5 }
+    }
+}
+
+/// Frame times measured over the last few frames.
+#[derive(Debug, Clone, Default)]
+pub struct FrameStats {
+    frames: u64,
+    recent: VecDeque<Duration>,
+}
+
+impl FrameStats {
+    /// Adds the time the last frame took.
+    pub fn record(&mut self, frame_time: Duration) {
+        self.frames += 1;
+        if self.recent.len() == STATS_WINDOW {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(frame_time);
+    }
+
+    /// Frames measured since the loop started.
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    pub fn last(&self) -> Duration {
+        self.recent.back().copied().unwrap_or_default()
+    }
+
+    pub fn average(&self) -> Duration {
+        if self.recent.is_empty() {
+            return Duration::ZERO;
+        }
+        self.recent.iter().sum::<Duration>() / self.recent.len() as u32
+    }
+
+    pub fn min(&self) -> Duration {
+        self.recent.iter().min().copied().unwrap_or_default()
+    }
+
+    pub fn max(&self) -> Duration {
+        self.recent.iter().max().copied().unwrap_or_default()
+    }
+
+    pub fn fps(&self) -> f64 {
+        let average = self.average().as_secs_f64();
+        if average > 0.0 { 1.0 / average } else { 0.0 }
+    }
+}
+
+/// Fixed-timestep pacing: how many updates a frame should run, how far rendering is
+/// between two updates, and how long to wait before the next frame.
+///
+/// A *simulated* loop never waits and advances time by exactly one tick per frame, so
+/// headless and replayed runs behave the same on any machine.
+pub struct MainLoop {
+    config: LoopConfig,
+    simulated: bool,
+    tick: Duration,
+    accumulator: Duration,
+    pending_ticks: u32,
+    frame_start: Option<Instant>,
+    stats: FrameStats,
+}
+
+impl MainLoop {
+    pub fn new(config: LoopConfig, simulated: bool) -> MainLoop {
+        let tick = Duration::from_secs(1) / config.tick_rate.max(1);
+
+        MainLoop {
+            config,
+            simulated,
+            tick,
+            accumulator: Duration::ZERO,
+            pending_ticks: 0,
+            frame_start: None,
+            stats: FrameStats::default(),
+        }
+    }
+
+    /// Starts timing a new frame and works out how many updates it owes.
+    pub fn begin_frame(&mut self) {
+        let now = Instant::now();
+        let elapsed = match self.frame_start {
+            None => Duration::ZERO,
+            Some(start) => {
+                let elapsed = now - start;
+                self.stats.record(elapsed);
+                elapsed
+            }
+        };
+        self.frame_start = Some(now);
+
+        let elapsed = if self.simulated { self.tick } else { elapsed };
+        let max_backlog = self.tick * self.config.max_ticks_per_frame;
+        self.accumulator = (self.accumulator + elapsed).min(max_backlog);
+
+        self.pending_ticks = 0;
+        while self.accumulator >= self.tick {
+            self.accumulator -= self.tick;
+            self.pending_ticks += 1;
+        }
+    }
+
+    /// Number of fixed updates to run this frame, each lasting [`MainLoop::tick`].
+    pub fn ticks(&self) -> u32 {
+        self.pending_ticks
+    }
+
+    /// Length of one fixed update.
+    pub fn tick(&self) -> Duration {
+        self.tick
+    }
+
+    /// How far the frame is between the last update and the next one, in `0.0..1.0`,
+    /// for interpolating what is drawn.
+    pub fn alpha(&self) -> f32 {
+        self.accumulator.as_secs_f32() / self.tick.as_secs_f32()
+    }
+
+    /// Time left before the next frame is due, which can be spent waiting for input.
+    pub fn time_left(&self) -> Option<Duration> {
+        if self.simulated {
+            return None;
+        }
+
+        let frame_time = Duration::from_secs(1) / self.config.frame_cap?.max(1);
+        let spent = self.frame_start.map(|start| start.elapsed()).unwrap_or_default();
+        Some(frame_time.saturating_sub(spent))
+    }
+
+    /// Sleeps off whatever is left of the frame.
+    pub fn end_frame(&mut self) {
+        if let Some(left) = self.time_left() {
+            thread::sleep(left);
+        }
+    }
+
+    pub fn stats(&self) -> &FrameStats {
+        &self.stats
+    }
+}
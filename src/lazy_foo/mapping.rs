@@ -0,0 +1,426 @@
+use std::env;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use sdl2::GameControllerSubsystem;
+use sdl2::controller::{Axis, Button};
+use sdl2::joystick;
+use crate::Error;
+use crate::lazy_foo::assets::Assets;
+use crate::lazy_foo::input::{self, AxisDirection};
+
+/// Environment variable SDL reads extra mappings from, one per line.
+pub const CONFIG_VAR: &str = "SDL_GAMECONTROLLERCONFIG";
+
+/// Fields SDL accepts in a mapping besides bindings, kept as they are.
+const EXTRA_FIELDS: [&str; 4] = ["hint", "crc", "sdk>=", "sdk<="];
+
+/// Where mappings made with the mapping wizard are kept, `mappings.txt` in
+/// [`input::user_config_dir`].
+pub fn user_mappings_path() -> Option<PathBuf> {
+    Some(input::user_config_dir()?.join("mappings.txt"))
+}
+
+/// A malformed line, skipped when its file was loaded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MappingError {
+    /// The file or variable the line came from.
+    pub source: String,
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for MappingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}, line {}: {}", self.source, self.line, self.message)
+    }
+}
+
+/// The platforms SDL tags mappings with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Platform {
+    Windows,
+    MacOs,
+    Linux,
+    Android,
+    Ios,
+}
+
+impl Platform {
+    pub const ALL: [Platform; 5] = [Platform::Windows, Platform::MacOs, Platform::Linux, Platform::Android, Platform::Ios];
+
+    /// The name used in `platform:` fields, which is also what `SDL_GetPlatform` returns.
+    pub fn name(self) -> &'static str {
+        match self {
+            Platform::Windows => "Windows",
+            Platform::MacOs => "Mac OS X",
+            Platform::Linux => "Linux",
+            Platform::Android => "Android",
+            Platform::Ios => "iOS",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Platform> {
+        Platform::ALL.into_iter().find(|platform| platform.name() == name)
+    }
+
+    /// The platform SDL is running on, if mappings can be tagged with it.
+    pub fn current() -> Option<Platform> {
+        Platform::from_name(sdl2::get_platform())
+    }
+}
+
+impl fmt::Display for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A joystick GUID, written as 32 hex digits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Guid(pub [u8; 16]);
+
+impl Guid {
+    pub fn parse(text: &str) -> Result<Guid, String> {
+        if text.len() != 32 || !text.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return Err(format!("GUID '{}' is not 32 hex digits", text));
+        }
+
+        let mut bytes = [0; 16];
+        for (index, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&text[index * 2..index * 2 + 2], 16).unwrap();
+        }
+        Ok(Guid(bytes))
+    }
+}
+
+impl From<joystick::Guid> for Guid {
+    fn from(guid: joystick::Guid) -> Guid {
+        Guid(guid.raw().data)
+    }
+}
+
+impl fmt::Display for Guid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in self.0 {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+/// The controller input a binding produces, left of the `:`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Target {
+    Button(Button),
+    Axis(Axis),
+    /// One direction of an axis, written `+leftx` or `-leftx`.
+    HalfAxis(Axis, AxisDirection),
+}
+
+impl Target {
+    pub fn parse(text: &str) -> Result<Target, String> {
+        let axis = |name: &str| Axis::from_string(name).ok_or(format!("unknown controller axis '{}'", name));
+
+        if let Some(name) = text.strip_prefix('+') {
+            return Ok(Target::HalfAxis(axis(name)?, AxisDirection::Positive));
+        }
+        if let Some(name) = text.strip_prefix('-') {
+            return Ok(Target::HalfAxis(axis(name)?, AxisDirection::Negative));
+        }
+
+        Button::from_string(text).map(Target::Button)
+            .or_else(|| Axis::from_string(text).map(Target::Axis))
+            .ok_or(format!("unknown controller button or axis '{}'", text))
+    }
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Target::Button(button) => f.write_str(&button.string()),
+            Target::Axis(axis) => f.write_str(&axis.string()),
+            Target::HalfAxis(axis, AxisDirection::Positive) => write!(f, "+{}", axis.string()),
+            Target::HalfAxis(axis, AxisDirection::Negative) => write!(f, "-{}", axis.string()),
+        }
+    }
+}
+
+/// The joystick input a binding reads, right of the `:`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Source {
+    /// `b<n>`.
+    Button(u32),
+    /// `a<n>`, `+a<n>` or `-a<n>` for half of the axis, with a trailing `~` if inverted.
+    Axis { index: u32, half: Option<AxisDirection>, inverted: bool },
+    /// `h<n>.<mask>`, the mask being 1 for up, 2 right, 4 down and 8 left.
+    Hat { index: u32, mask: u8 },
+}
+
+impl Source {
+    pub fn parse(text: &str) -> Result<Source, String> {
+        let number = |digits: &str| digits.parse::<u32>()
+            .map_err(|_| format!("'{}' is not a joystick input number", digits));
+
+        if let Some(index) = text.strip_prefix('b') {
+            return Ok(Source::Button(number(index)?));
+        }
+        if let Some(hat) = text.strip_prefix('h') {
+            let (index, mask) = hat.split_once('.').ok_or(format!("hat '{}' needs a mask, as in h0.1", text))?;
+            let mask = match mask.parse::<u8>() {
+                Ok(mask) if (1..=15).contains(&mask) => mask,
+                _ => return Err(format!("'{}' is not a hat mask from 1 to 15", mask))
+            };
+            return Ok(Source::Hat { index: number(index)?, mask });
+        }
+
+        let (half, rest) = match text.strip_prefix('+') {
+            Some(rest) => (Some(AxisDirection::Positive), rest),
+            None => match text.strip_prefix('-') {
+                Some(rest) => (Some(AxisDirection::Negative), rest),
+                None => (None, text)
+            }
+        };
+        let (inverted, rest) = match rest.strip_suffix('~') {
+            Some(rest) => (true, rest),
+            None => (false, rest)
+        };
+        match rest.strip_prefix('a') {
+            None => Err(format!("'{}' is not a joystick button, axis or hat", text)),
+            Some(index) => Ok(Source::Axis { index: number(index)?, half, inverted })
+        }
+    }
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Source::Button(index) => write!(f, "b{}", index),
+            Source::Hat { index, mask } => write!(f, "h{}.{}", index, mask),
+            Source::Axis { index, half, inverted } => {
+                match half {
+                    None => (),
+                    Some(AxisDirection::Positive) => f.write_str("+")?,
+                    Some(AxisDirection::Negative) => f.write_str("-")?,
+                }
+                write!(f, "a{}", index)?;
+                if inverted {
+                    f.write_str("~")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// One GameControllerDB line: which joystick inputs make up each controller input.
+///
+/// Written as `<guid>,<name>,<target>:<source>,...,platform:<platform>,`, e.g.
+/// `03000000790000000600000010010000,My pad,a:b2,leftx:a0,dpup:h0.1,platform:Linux,`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mapping {
+    pub guid: Guid,
+    pub name: String,
+    pub bindings: Vec<(Target, Source)>,
+    /// The platform the mapping is for, or `None` if it fits every platform.
+    pub platform: Option<Platform>,
+    /// Fields SDL understands but this module does not, such as `hint` and `crc`.
+    pub extra: Vec<(String, String)>,
+}
+
+impl Mapping {
+    pub fn new(guid: Guid, name: &str) -> Mapping {
+        Mapping { guid, name: name.to_string(), bindings: Vec::new(), platform: None, extra: Vec::new() }
+    }
+
+    pub fn parse(line: &str) -> Result<Mapping, String> {
+        let mut fields = line.trim().split(',');
+        let guid = Guid::parse(fields.next().unwrap_or_default())?;
+        let name = fields.next().unwrap_or_default();
+        if name.is_empty() {
+            return Err("the mapping has no controller name".to_string());
+        }
+
+        let mut mapping = Mapping::new(guid, name);
+        for field in fields.filter(|field| !field.is_empty()) {
+            let (key, value) = field.split_once(':')
+                .ok_or(format!("'{}' is not <target>:<source>", field))?;
+
+            if key == "platform" {
+                if mapping.platform.is_some() {
+                    return Err("the platform is given twice".to_string());
+                }
+                let platform = Platform::from_name(value).ok_or(format!("unknown platform '{}'", value))?;
+                mapping.platform = Some(platform);
+            } else if EXTRA_FIELDS.contains(&key) {
+                mapping.extra.push((key.to_string(), value.to_string()));
+            } else {
+                let target = Target::parse(key)?;
+                if mapping.source(target).is_some() {
+                    return Err(format!("'{}' is bound twice", key));
+                }
+                mapping.bindings.push((target, Source::parse(value)?));
+            }
+        }
+
+        Ok(mapping)
+    }
+
+    /// The joystick input bound to `target`, if any.
+    pub fn source(&self, target: Target) -> Option<Source> {
+        self.bindings.iter()
+            .find(|(bound, _)| *bound == target)
+            .map(|(_, source)| *source)
+    }
+
+    /// Whether SDL would use this mapping on `platform`.
+    pub fn fits(&self, platform: Platform) -> bool {
+        self.platform.is_none_or(|own| own == platform)
+    }
+}
+
+impl fmt::Display for Mapping {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{},", self.guid, self.name)?;
+        for (target, source) in &self.bindings {
+            write!(f, "{}:{},", target, source)?;
+        }
+        for (key, value) in &self.extra {
+            write!(f, "{}:{},", key, value)?;
+        }
+        if let Some(platform) = self.platform {
+            write!(f, "platform:{},", platform)?;
+        }
+        Ok(())
+    }
+}
+
+/// Mappings gathered from GameControllerDB files and `$SDL_GAMECONTROLLERCONFIG`.
+///
+/// Blank lines and `#` comments are skipped. Malformed lines are skipped too and kept in
+/// [`MappingDb::errors`], so one bad entry does not cost every other controller its mapping.
+/// A mapping merged in later replaces an earlier one for the same GUID and platform.
+#[derive(Debug, Clone, Default)]
+pub struct MappingDb {
+    mappings: Vec<Mapping>,
+    errors: Vec<MappingError>,
+}
+
+impl MappingDb {
+    pub fn new() -> MappingDb {
+        MappingDb::default()
+    }
+
+    /// Parses the lines of `text`, naming `source` in the errors of malformed ones.
+    pub fn parse(source: &str, text: &str) -> MappingDb {
+        let mut db = MappingDb::new();
+
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            match Mapping::parse(line) {
+                Ok(mapping) => db.insert(mapping),
+                Err(message) => db.errors.push(MappingError { source: source.to_string(), line: index + 1, message })
+            }
+        }
+
+        db
+    }
+
+    pub fn load(path: &Path) -> Result<MappingDb, Error> {
+        let text = fs::read_to_string(path)
+            .map_err(|source| Error::MappingFile { path: path.display().to_string(), source })?;
+        Ok(MappingDb::parse(&path.display().to_string(), &text))
+    }
+
+    /// Adds `mapping` as a new line at the end of the file at `path`, creating it if needed.
+    ///
+    /// Earlier lines for the same controller are left alone; the new one wins when loaded.
+    pub fn append(path: &Path, mapping: &Mapping) -> Result<(), Error> {
+        let mapping_error = |source| Error::MappingFile { path: path.display().to_string(), source };
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(mapping_error)?;
+        }
+        let mut file = OpenOptions::new().create(true).append(true).open(path).map_err(mapping_error)?;
+        writeln!(file, "{}", mapping).map_err(mapping_error)
+    }
+
+    /// Loads the asset at `path`, from disk or from the embedded copy.
+    pub fn load_asset(assets: &Assets, path: &str) -> Result<MappingDb, Error> {
+        let mut text = String::new();
+        assets.open(path)?
+            .read_to_string(&mut text)
+            .map_err(|source| Error::MappingFile { path: path.to_string(), source })?;
+        Ok(MappingDb::parse(path, &text))
+    }
+
+    /// The mappings in [`CONFIG_VAR`], empty if it is not set.
+    pub fn from_env() -> MappingDb {
+        match env::var(CONFIG_VAR) {
+            Err(_) => MappingDb::new(),
+            Ok(text) => MappingDb::parse(&format!("${}", CONFIG_VAR), &text)
+        }
+    }
+
+    /// Adds `mapping`, replacing the one for the same GUID and platform.
+    pub fn insert(&mut self, mapping: Mapping) {
+        let same = |other: &Mapping| other.guid == mapping.guid && other.platform == mapping.platform;
+        match self.mappings.iter_mut().find(|other| same(other)) {
+            Some(other) => *other = mapping,
+            None => self.mappings.push(mapping)
+        }
+    }
+
+    /// Adds every mapping of `other` over these, keeping the errors of both.
+    pub fn merge(&mut self, other: MappingDb) {
+        for mapping in other.mappings {
+            self.insert(mapping);
+        }
+        self.errors.extend(other.errors);
+    }
+
+    pub fn mappings(&self) -> &[Mapping] {
+        &self.mappings
+    }
+
+    /// Lines skipped because they could not be parsed, in the order they were read.
+    pub fn errors(&self) -> &[MappingError] {
+        &self.errors
+    }
+
+    /// The mapping SDL should use for `guid` on `platform`, preferring one tagged with it.
+    pub fn get(&self, guid: Guid, platform: Platform) -> Option<&Mapping> {
+        let fitting = || self.mappings.iter().filter(|mapping| mapping.guid == guid && mapping.fits(platform));
+        fitting().find(|mapping| mapping.platform.is_some()).or_else(|| fitting().next())
+    }
+
+    /// One mapping per GUID for `platform`, as [`MappingDb::get`] picks them.
+    pub fn for_platform(&self, platform: Platform) -> Vec<&Mapping> {
+        let mut mappings: Vec<&Mapping> = Vec::new();
+        for mapping in &self.mappings {
+            if mappings.iter().all(|chosen| chosen.guid != mapping.guid) {
+                mappings.extend(self.get(mapping.guid, platform));
+            }
+        }
+        mappings
+    }
+
+    /// Hands SDL the mappings for `platform`, returning how many it took.
+    ///
+    /// A mapping SDL refuses is reported as a warning and skipped.
+    pub fn apply(&self, game_controller: &GameControllerSubsystem, platform: Platform) -> usize {
+        let mut applied = 0;
+        for mapping in self.for_platform(platform) {
+            match game_controller.add_mapping(&mapping.to_string()) {
+                Err(error) => println!("Warning: SDL refused the mapping for {}! {}", mapping.name, error),
+                Ok(_) => applied += 1
+            }
+        }
+        applied
+    }
+}
@@ -0,0 +1,353 @@
+use sdl2::controller::{Axis, Button};
+use sdl2::event::Event;
+use sdl2::joystick::{HatState, Joystick};
+use sdl2::keyboard::Keycode;
+use sdl2::pixels::Color;
+use sdl2::render::WindowCanvas;
+use crate::Error;
+use crate::lazy_foo::app::{App, Options, SCREEN_WIDTH};
+use crate::lazy_foo::input::AxisDirection;
+use crate::lazy_foo::mapping::{Guid, Mapping, Platform, Source, Target};
+use crate::lazy_foo::scene::{self, Scene, Textures, Transition};
+use crate::lazy_foo::text::{Align, Font, TextStyle};
+
+const TITLE: &str = "Map a controller";
+const HELP: &str = "Space: skip  Backspace: go back  Enter: finish now";
+
+/// How far an axis has to move from where it rests to count.
+const AXIS_THRESHOLD: i32 = 16000;
+
+const BACKGROUND: Color = Color::RGB(0x10, 0x10, 0x30);
+const PROMPT: Color = Color::YELLOW;
+const TEXT: Color = Color::WHITE;
+const DIM: Color = Color::RGB(0x80, 0x80, 0x80);
+
+/// What the user is asked to do, in order, and the controller input it maps.
+const STEPS: [(Target, &str); 21] = [
+    (Target::Button(Button::A), "Press the bottom face button (A)"),
+    (Target::Button(Button::B), "Press the right face button (B)"),
+    (Target::Button(Button::X), "Press the left face button (X)"),
+    (Target::Button(Button::Y), "Press the top face button (Y)"),
+    (Target::Button(Button::Back), "Press Back (Select)"),
+    (Target::Button(Button::Guide), "Press Guide (Home)"),
+    (Target::Button(Button::Start), "Press Start"),
+    (Target::Button(Button::LeftShoulder), "Press the left shoulder button"),
+    (Target::Button(Button::RightShoulder), "Press the right shoulder button"),
+    (Target::Axis(Axis::TriggerLeft), "Pull the left trigger"),
+    (Target::Axis(Axis::TriggerRight), "Pull the right trigger"),
+    (Target::Button(Button::LeftStick), "Click the left stick"),
+    (Target::Button(Button::RightStick), "Click the right stick"),
+    (Target::Button(Button::DPadUp), "Press up on the d-pad"),
+    (Target::Button(Button::DPadDown), "Press down on the d-pad"),
+    (Target::Button(Button::DPadLeft), "Press left on the d-pad"),
+    (Target::Button(Button::DPadRight), "Press right on the d-pad"),
+    (Target::Axis(Axis::LeftX), "Push the left stick right"),
+    (Target::Axis(Axis::LeftY), "Push the left stick down"),
+    (Target::Axis(Axis::RightX), "Push the right stick right"),
+    (Target::Axis(Axis::RightY), "Push the right stick down"),
+];
+
+/// The joystick being mapped, with where its axes rest.
+struct Device {
+    joystick: Joystick,
+    rest: Vec<i16>,
+}
+
+impl Device {
+    fn open(app: &mut App, index: u32) -> Result<Device, Error> {
+        let joystick = app.joystick()?
+            .open(index)
+            .map_err(|source| Error::JoystickOpen { index, source })?;
+        let rest = (0..joystick.num_axes())
+            .map(|axis| joystick.axis(axis).unwrap_or(0))
+            .collect();
+
+        Ok(Device { joystick, rest })
+    }
+
+    fn rest(&self, axis: u8) -> i32 {
+        self.rest.get(axis as usize).copied().unwrap_or(0) as i32
+    }
+
+    fn moved(&self, axis: u8, value: i16) -> i32 {
+        value as i32 - self.rest(axis)
+    }
+
+    /// The joystick input `event` maps to `target`, if it is one this device pressed.
+    fn source(&self, target: Target, event: &Event) -> Option<Source> {
+        match *event {
+            Event::JoyButtonDown { which, button_idx, .. } if which == self.joystick.instance_id() => {
+                Some(Source::Button(button_idx as u32))
+            },
+            // Only a single direction: diagonals are two d-pad buttons at once.
+            Event::JoyHatMotion { which, hat_idx, state, .. }
+                if which == self.joystick.instance_id()
+                    && matches!(state, HatState::Up | HatState::Right | HatState::Down | HatState::Left) =>
+            {
+                match target {
+                    Target::Button(_) => Some(Source::Hat { index: hat_idx as u32, mask: state.to_raw() }),
+                    _ => None
+                }
+            },
+            Event::JoyAxisMotion { which, axis_idx, value, .. } if which == self.joystick.instance_id() => {
+                let moved = self.moved(axis_idx, value);
+                if moved.abs() < AXIS_THRESHOLD {
+                    return None;
+                }
+                let index = axis_idx as u32;
+                let direction = if moved > 0 { AxisDirection::Positive } else { AxisDirection::Negative };
+
+                Some(match target {
+                    // Asked for right and down, which are positive on a controller.
+                    Target::Axis(Axis::LeftX | Axis::LeftY | Axis::RightX | Axis::RightY) => {
+                        Source::Axis { index, half: None, inverted: direction == AxisDirection::Negative }
+                    },
+                    // A trigger resting at one end uses the whole axis, one resting in the middle half of it.
+                    Target::Axis(_) if self.rest(axis_idx) < -AXIS_THRESHOLD => {
+                        Source::Axis { index, half: None, inverted: false }
+                    },
+                    _ => Source::Axis { index, half: Some(direction), inverted: false }
+                })
+            },
+            _ => None
+        }
+    }
+
+    /// Whether `event` lets go of `source`.
+    fn releases(&self, source: Source, event: &Event) -> bool {
+        match (source, event) {
+            (Source::Button(index), Event::JoyButtonUp { which, button_idx, .. }) => {
+                *which == self.joystick.instance_id() && *button_idx as u32 == index
+            },
+            (Source::Hat { index, .. }, Event::JoyHatMotion { which, hat_idx, state, .. }) => {
+                *which == self.joystick.instance_id() && *hat_idx as u32 == index && *state == HatState::Centered
+            },
+            (Source::Axis { index, .. }, Event::JoyAxisMotion { which, axis_idx, value, .. }) => {
+                *which == self.joystick.instance_id()
+                    && *axis_idx as u32 == index
+                    && self.moved(*axis_idx, *value).abs() < AXIS_THRESHOLD / 2
+            },
+            _ => false
+        }
+    }
+}
+
+/// Walks the user through pressing every button and moving every axis of a joystick SDL
+/// has no mapping for, then appends the GameControllerDB line it makes to the app's user
+/// mapping file, which every controller lesson loads.
+///
+/// Each input is only taken once it is let go of again, so one press never fills two steps.
+pub struct MappingWizard {
+    device: Option<Device>,
+    step: usize,
+    bindings: Vec<(Target, Source)>,
+    /// Input just bound, waiting to be let go of before the next step.
+    held: Option<Source>,
+    /// The finished mapping, once every step is done.
+    mapping: Option<Mapping>,
+    status: String,
+    font: Option<Font>,
+}
+
+impl MappingWizard {
+    pub fn boxed() -> Box<dyn Scene> {
+        Box::new(MappingWizard {
+            device: None,
+            step: 0,
+            bindings: Vec::new(),
+            held: None,
+            mapping: None,
+            status: String::new(),
+            font: None,
+        })
+    }
+
+    fn restart(&mut self) {
+        self.step = 0;
+        self.bindings.clear();
+        self.held = None;
+        self.mapping = None;
+        self.status.clear();
+    }
+
+    fn open(&mut self, app: &mut App, index: u32) -> Result<(), Error> {
+        self.device = Some(Device::open(app, index)?);
+        self.restart();
+        Ok(())
+    }
+
+    fn next_step(&mut self, app: &mut App) -> Result<(), Error> {
+        self.step += 1;
+        if self.step == STEPS.len() {
+            self.finish(app)?;
+        }
+        Ok(())
+    }
+
+    /// Undoes the binding just made, or else goes back to the step before.
+    fn back(&mut self) {
+        let current = STEPS[self.step].0;
+        if self.held.take().is_some() {
+            self.bindings.retain(|(bound, _)| *bound != current);
+        } else if self.step > 0 {
+            self.step -= 1;
+            let target = STEPS[self.step].0;
+            self.bindings.retain(|(bound, _)| *bound != target);
+        }
+    }
+
+    /// Builds the mapping from the bindings made so far and saves it.
+    fn finish(&mut self, app: &mut App) -> Result<(), Error> {
+        let device = match &self.device {
+            None => return Ok(()),
+            Some(device) => device
+        };
+        self.step = STEPS.len();
+
+        let mut mapping = Mapping::new(Guid::from(device.joystick.guid()), &device.joystick.name());
+        mapping.bindings = self.bindings.clone();
+        mapping.platform = Platform::current();
+
+        self.status = match app.add_mapping(mapping.clone()) {
+            Err(error) => error.to_string(),
+            Ok(()) => match app.user_mappings_path() {
+                None => "Added for this run only".to_string(),
+                Some(path) => format!("Saved to {}", path.display())
+            }
+        };
+        self.mapping = Some(mapping);
+        Ok(())
+    }
+
+    fn capture(&mut self, app: &mut App, event: &Event) -> Result<(), Error> {
+        let device = match &self.device {
+            None => return Ok(()),
+            Some(device) => device
+        };
+
+        if let Some(held) = self.held {
+            if device.releases(held, event) {
+                self.held = None;
+                self.next_step(app)?;
+            }
+            return Ok(());
+        }
+
+        let target = STEPS[self.step].0;
+        if let Some(source) = device.source(target, event) {
+            // A noisy axis or a button already used elsewhere is not what the user meant.
+            if self.bindings.iter().all(|(_, bound)| *bound != source) {
+                self.bindings.push((target, source));
+                self.held = Some(source);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Scene for MappingWizard {
+    fn on_enter(&mut self, app: &mut App) -> Result<(), Error> {
+        self.font = Some(Font::builtin()?);
+
+        // Loads the mappings first, so the new one is added over them rather than under.
+        app.mapped_game_controller()?;
+        let joysticks = app.joystick()?
+            .num_joysticks()
+            .map_err(Error::sdl(Error::JoystickSubsystem))?;
+        if joysticks > 0 {
+            self.open(app, 0)?;
+        }
+
+        Ok(())
+    }
+
+    fn on_exit(&mut self, _app: &mut App) -> Result<(), Error> {
+        self.device = None;
+        Ok(())
+    }
+
+    fn on_event(&mut self, app: &mut App, event: &Event) -> Result<Transition, Error> {
+        let unplugged = |which| self.device.as_ref().is_some_and(|device| device.joystick.instance_id() == which);
+
+        match *event {
+            Event::JoyDeviceAdded { which, .. } if self.device.is_none() => self.open(app, which)?,
+            Event::JoyDeviceRemoved { which, .. } if unplugged(which) => {
+                self.device = None;
+                self.restart();
+            },
+            Event::KeyDown { keycode: Some(keycode), repeat: false, .. } if self.device.is_some() => {
+                match (keycode, &self.mapping) {
+                    (Keycode::R, Some(_)) => self.restart(),
+                    (_, Some(_)) => (),
+                    (Keycode::Space, None) => {
+                        self.held = None;
+                        self.next_step(app)?;
+                    },
+                    (Keycode::Backspace, None) => self.back(),
+                    (Keycode::Return | Keycode::KpEnter, None) => self.finish(app)?,
+                    _ => ()
+                }
+            },
+            _ if self.mapping.is_none() => self.capture(app, event)?,
+            _ => ()
+        }
+
+        Ok(Transition::Stay)
+    }
+
+    fn render(&mut self, canvas: &mut WindowCanvas, textures: &mut Textures) -> Result<(), Error> {
+        canvas.set_draw_color(BACKGROUND);
+        canvas.clear();
+
+        let font = match &self.font {
+            None => return Ok(()),
+            Some(font) => font
+        };
+        let mut font = textures.font(font)?;
+        let center = SCREEN_WIDTH as i32 / 2;
+        let wrapped = |color| TextStyle::new(color).align(Align::Center).wrap(SCREEN_WIDTH - 32);
+
+        font.draw(canvas, TITLE, center, 16, &TextStyle::new(TEXT).scale(3).align(Align::Center))?;
+
+        let device = match &self.device {
+            None => {
+                font.draw(canvas, "Plug in a joystick", center, 200, &TextStyle::new(PROMPT).scale(2).align(Align::Center))?;
+                return Ok(());
+            },
+            Some(device) => device
+        };
+        let joystick = format!("{} ({})", device.joystick.name(), device.joystick.guid().string());
+        font.draw(canvas, &joystick, center, 64, &wrapped(DIM))?;
+
+        match &self.mapping {
+            Some(mapping) => {
+                font.draw(canvas, "Done!", center, 120, &TextStyle::new(PROMPT).scale(2).align(Align::Center))?;
+                font.draw(canvas, &mapping.to_string(), center, 168, &wrapped(TEXT))?;
+                font.draw(canvas, &self.status, center, 408, &wrapped(PROMPT))?;
+                font.draw(canvas, "R: map it again", center, 448, &TextStyle::new(DIM).align(Align::Center))?;
+            },
+            None => {
+                let (_, prompt) = STEPS[self.step];
+                let progress = format!("Step {} of {}", self.step + 1, STEPS.len());
+                font.draw(canvas, &progress, center, 104, &TextStyle::new(DIM).align(Align::Center))?;
+                font.draw(canvas, prompt, center, 128, &TextStyle::new(PROMPT).scale(2).align(Align::Center))?;
+                if self.held.is_some() {
+                    font.draw(canvas, "Now let go", center, 160, &TextStyle::new(TEXT).align(Align::Center))?;
+                }
+
+                let bindings: Vec<String> = self.bindings.iter()
+                    .map(|(target, source)| format!("{}:{}", target, source))
+                    .collect();
+                font.draw(canvas, &bindings.join(", "), center, 216, &wrapped(TEXT))?;
+                font.draw(canvas, HELP, center, 448, &TextStyle::new(DIM).align(Align::Center))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub fn run(options: &Options) -> Result<(), Error> {
+    scene::run(options, vec![MappingWizard::boxed()])
+}
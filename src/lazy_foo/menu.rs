@@ -0,0 +1,202 @@
+use std::cell::Cell;
+use std::rc::Rc;
+use sdl2::controller::Button;
+use sdl2::event::Event;
+use sdl2::image::InitFlag;
+use sdl2::keyboard::Keycode;
+use sdl2::pixels::Color;
+use sdl2::render::WindowCanvas;
+use crate::Error;
+use crate::lazy_foo::app::{App, AppBuilder, Options, SCREEN_WIDTH};
+use crate::lazy_foo::controllers::Controllers;
+use crate::lazy_foo::scene::{self, Scene, Textures, Transition};
+use crate::lazy_foo::text::{Align, Font, TextStyle};
+use crate::lazy_foo::{Lesson, SurfaceFn, LESSONS};
+
+const TITLE: &str = "Lazy Foo' SDL2 Tutorials";
+const HELP: &str = "Up/Down to choose, Enter to start, Escape to go back";
+
+const LIST_TOP: i32 = 88;
+const LINE_HEIGHT: i32 = 28;
+/// Lessons listed at once, so the list stays clear of the description below it.
+const VISIBLE_ROWS: usize = 10;
+
+const BACKGROUND: Color = Color::RGB(0x10, 0x10, 0x30);
+const UNSELECTED: Color = Color::WHITE;
+const SELECTED: Color = Color::YELLOW;
+/// Lessons behind a cargo feature this build does not have can not be started.
+const UNAVAILABLE: Color = Color::RGB(0x80, 0x80, 0x80);
+
+/// A lesson drawing on a window surface, which the menu leaves its window for.
+#[derive(Clone, Copy)]
+struct Launch {
+    lesson: usize,
+    surface: SurfaceFn,
+    /// Frames the menu's window went through before it was closed.
+    frames: u32,
+}
+
+/// Lists every lesson and starts the chosen one on top of itself.
+///
+/// Escape in a lesson comes back here; Escape in the menu quits. Lessons drawing on the
+/// window surface can not share the menu's renderer, so for those the menu closes its
+/// window and [`run`] opens it again once the lesson is over.
+pub struct Menu {
+    lessons: &'static [Lesson],
+    selected: usize,
+    /// First lesson listed; scrolled so the selected one is always in view.
+    first: usize,
+    controllers: Controllers,
+    font: Option<Font>,
+    launch: Rc<Cell<Option<Launch>>>,
+}
+
+impl Menu {
+    fn boxed(selected: usize, launch: Rc<Cell<Option<Launch>>>) -> Box<dyn Scene> {
+        let mut menu = Menu {
+            lessons: LESSONS,
+            selected,
+            first: 0,
+            controllers: Controllers::new(),
+            font: None,
+            launch,
+        };
+        menu.move_selection(0);
+        Box::new(menu)
+    }
+
+    fn move_selection(&mut self, step: isize) {
+        let count = self.lessons.len() as isize;
+        self.selected = (self.selected as isize + step).rem_euclid(count) as usize;
+
+        if self.selected < self.first {
+            self.first = self.selected;
+        } else if self.selected >= self.first + VISIBLE_ROWS {
+            self.first = self.selected + 1 - VISIBLE_ROWS;
+        }
+    }
+
+    fn start(&self, app: &App) -> Transition {
+        let lesson = &self.lessons[self.selected];
+        match (lesson.scene, lesson.surface) {
+            (Some(scene), _) => Transition::Push(scene()),
+            (None, Some(surface)) => {
+                self.launch.set(Some(Launch { lesson: self.selected, surface, frames: app.frames() }));
+                Transition::Quit
+            },
+            (None, None) => Transition::Stay
+        }
+    }
+}
+
+impl Scene for Menu {
+    fn on_enter(&mut self, app: &mut App) -> Result<(), Error> {
+        self.font = Some(Font::builtin()?);
+        self.controllers.open_all(app)
+    }
+
+    fn on_exit(&mut self, _app: &mut App) -> Result<(), Error> {
+        self.controllers.close_all();
+        Ok(())
+    }
+
+    fn on_event(&mut self, app: &mut App, event: &Event) -> Result<Transition, Error> {
+        self.controllers.handle_event(app, event)?;
+
+        match *event {
+            Event::KeyDown { keycode: Some(keycode), .. } => match keycode {
+                Keycode::Up => self.move_selection(-1),
+                Keycode::Down => self.move_selection(1),
+                Keycode::Return | Keycode::KpEnter | Keycode::Space => return Ok(self.start(app)),
+                Keycode::Escape => return Ok(Transition::Quit),
+                _ => ()
+            },
+            Event::ControllerButtonDown { button, .. } => match button {
+                Button::DPadUp => self.move_selection(-1),
+                Button::DPadDown => self.move_selection(1),
+                Button::A | Button::Start => return Ok(self.start(app)),
+                _ => ()
+            },
+            _ => ()
+        }
+
+        Ok(Transition::Stay)
+    }
+
+    fn render(&mut self, canvas: &mut WindowCanvas, textures: &mut Textures) -> Result<(), Error> {
+        canvas.set_draw_color(BACKGROUND);
+        canvas.clear();
+
+        let font = match &self.font {
+            None => return Ok(()),
+            Some(font) => font
+        };
+        let mut font = textures.font(font)?;
+        let center = SCREEN_WIDTH as i32 / 2;
+
+        let title_style = TextStyle::new(Color::WHITE).scale(3).align(Align::Center);
+        font.draw(canvas, TITLE, center, 24, &title_style)?;
+
+        let last = self.lessons.len().min(self.first + VISIBLE_ROWS);
+        for (row, index) in (self.first..last).enumerate() {
+            let lesson = &self.lessons[index];
+            let selected = index == self.selected;
+            let marker = if selected { '>' } else { ' ' };
+            let color = match (lesson.scene.is_some() || lesson.surface.is_some(), selected) {
+                (false, _) => UNAVAILABLE,
+                (true, true) => SELECTED,
+                (true, false) => UNSELECTED,
+            };
+
+            let line = format!("{} {:>2}  {}", marker, index, lesson.name);
+            font.draw(canvas, &line, 48, LIST_TOP + row as i32 * LINE_HEIGHT, &TextStyle::new(color).scale(2))?;
+        }
+
+        // Arrows beside the list when there are lessons above or below it.
+        let arrow_style = TextStyle::new(UNAVAILABLE).scale(2);
+        if self.first > 0 {
+            font.draw(canvas, "^", SCREEN_WIDTH as i32 - 64, LIST_TOP, &arrow_style)?;
+        }
+        if last < self.lessons.len() {
+            let bottom = LIST_TOP + (VISIBLE_ROWS as i32 - 1) * LINE_HEIGHT;
+            font.draw(canvas, "v", SCREEN_WIDTH as i32 - 64, bottom, &arrow_style)?;
+        }
+
+        let lesson = &self.lessons[self.selected];
+        let color = if lesson.scene.is_some() || lesson.surface.is_some() { Color::WHITE } else { UNAVAILABLE };
+        let description_style = TextStyle::new(color).scale(2).align(Align::Center).wrap(SCREEN_WIDTH - 16);
+        font.draw(canvas, lesson.description, center, 392, &description_style)?;
+
+        font.draw(canvas, HELP, center, 448, &TextStyle::new(UNAVAILABLE).align(Align::Center))?;
+        Ok(())
+    }
+}
+
+pub fn run(options: &Options) -> Result<(), Error> {
+    let launch = Rc::new(Cell::new(None));
+    let mut options = options.clone();
+    let mut selected = 0;
+
+    loop {
+        scene::run(&options, vec![Menu::boxed(selected, Rc::clone(&launch))])?;
+        let Launch { lesson, surface, frames } = match launch.take() {
+            None => return Ok(()),
+            Some(launch) => launch
+        };
+
+        // The lesson's window carries on the frames, script and recording of the menu's.
+        options.frames_before = frames;
+        let mut app = AppBuilder::new("SDL Tutorial")
+            .options(&options)
+            .image(InitFlag::PNG)
+            .escape_quits()
+            .build()?;
+        surface(&mut app)?;
+
+        if options.frame_budget.is_some_and(|budget| app.frames() >= budget) {
+            return Ok(());
+        }
+        options.frames_before = app.frames();
+        selected = lesson;
+    }
+}
@@ -1,100 +1,47 @@
 use sdl2::event::Event;
+use sdl2::pixels::PixelFormat;
 use sdl2::rect::Rect;
-use sdl2::Sdl;
 use sdl2::surface::Surface;
-use sdl2::video::{Window, WindowSurfaceRef};
+use crate::Error;
+use crate::lazy_foo::app::{App, AppBuilder, Options, SCREEN_HEIGHT, SCREEN_WIDTH};
+use crate::lazy_foo::assets::Assets;
 
+const IMAGE_PATH: &str = "imgs/optimized_surface/stretch.bmp";
 
-const SCREEN_WIDTH: u32 = 640;
-const SCREEN_HEIGHT: u32 = 480;
-
-fn init() -> Option<(Sdl, Window)> {
-    let sdl_ctx = match sdl2::init() {
-        Err(error) => {
-            println!("SDL could not initialize! SDL_Error: {}", error);
-            return None;
-        },
-        Ok(sdl) =>  sdl
-    };
-
-    let windows_builder = sdl_ctx.video().unwrap()
-        .window("SDL Tutorial", SCREEN_WIDTH, SCREEN_HEIGHT);
-
-    let window = match windows_builder.build() {
-        Err(error) => {
-            println!("Window could not be created! SDL_Error: %{}", error);
-            return None;
-        },
-        Ok(window) => window
-    };
-
-    Some((sdl_ctx, window))
-}
-
-fn load_media<'a>(screen_surface: &WindowSurfaceRef) -> Option<Surface<'a>> {
-    let stretched_surface =
-        load_surface("imgs/optimized_surface/stretch.bmp", screen_surface);
-
-    if stretched_surface.is_none() {
-        println!("Failed to load stretching image!");
-    };
-
-    stretched_surface
+fn load_media(assets: &mut Assets, format: &PixelFormat) -> Result<Surface<'static>, Error> {
+    assets.surface(IMAGE_PATH)?.convert(format).map_err(Error::surface_convert(IMAGE_PATH))
 }
 
-fn load_surface<'a>(path: &str, screen_surface: &WindowSurfaceRef) -> Option<Surface<'a>> {
-    let loaded_surface = match Surface::load_bmp(path) {
-        Err(error) => {
-            println!("Unable to load image {}! SDL Error: {}", path, error);
-            return None;
-        },
-        Ok(surface) => surface
-    };
+pub fn run(options: &Options) -> Result<(), Error> {
+    let mut app = AppBuilder::new("SDL Tutorial")
+        .options(options)
+        .build()?;
 
-    match loaded_surface.convert(&screen_surface.pixel_format()) {
-        Err(error) => {
-            println!("Unable to optimize image {}! SDL Error: {}", path, error);
-            None
-        },
-        Ok(surface) => Some(surface)
-    }
+    run_in(&mut app)
 }
 
-pub fn run() {
-    let (sdl_ctx, window) = match init() {
-        None => {
-            println!("Failed to initialize!");
-            return
-        },
-        Some(tuple) => tuple
-    };
-
-    let mut event_pump = sdl_ctx.event_pump().unwrap();
-    let mut screen_surface = window.surface(&event_pump).unwrap();
-
-    let stretched_surface = match load_media(&screen_surface) {
-        None => {
-            println!("Failed to load media!");
-            return;
-        },
-        Some(surfaces) => surfaces
-    };
+pub fn run_in(app: &mut App) -> Result<(), Error> {
+    let format = app.window_surface()?.pixel_format();
+    let mut stretched_surface = load_media(app.assets(), &format)?;
 
     let stretch_rec = Rect::new(0, 0, SCREEN_WIDTH, SCREEN_HEIGHT);
 
-    'running: loop {
-        let event = match event_pump.poll_event() {
-            Some(event) => event,
-            None => continue
-        };
+    'running: while app.next_frame()? {
+        for event in app.poll_events()? {
+            if let Event::Quit {..} = event {
+                break 'running;
+            }
+        }
 
-        if let Event::Quit {..} = event {
-            break 'running;
+        if app.was_reloaded(IMAGE_PATH) {
+            stretched_surface = load_media(app.assets(), &format)?;
         }
 
-        screen_surface = window.surface(&event_pump).unwrap();
+        let mut screen_surface = app.window_surface()?;
         stretched_surface.blit_scaled(None, &mut screen_surface, stretch_rec)
-            .expect("Should blit correctly");
-        screen_surface.update_window().expect("Should update correctly");
+            .map_err(Error::sdl(Error::Render))?;
+        app.present()?;
     }
-}
\ No newline at end of file
+
+    Ok(())
+}
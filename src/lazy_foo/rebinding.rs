@@ -0,0 +1,260 @@
+use sdl2::controller::Button;
+use sdl2::event::Event;
+use sdl2::keyboard::{Keycode, Mod};
+use sdl2::pixels::Color;
+use sdl2::render::WindowCanvas;
+use crate::Error;
+use crate::lazy_foo::app::{App, Options, SCREEN_WIDTH};
+use crate::lazy_foo::controllers::Controllers;
+use crate::lazy_foo::input::{Action, Binding, AXIS_THRESHOLD};
+use crate::lazy_foo::scene::{self, Scene, Textures, Transition};
+use crate::lazy_foo::text::{Align, Font, TextStyle};
+
+const TITLE: &str = "Rebind controls";
+const HELP: &str = "Enter: add a binding  Delete: clear  R: reset  Shift+R: reset all";
+
+const LIST_TOP: i32 = 72;
+const LINE_HEIGHT: i32 = 44;
+const BINDINGS_LEFT: i32 = 200;
+
+const BACKGROUND: Color = Color::RGB(0x10, 0x10, 0x30);
+const SELECTED: Color = Color::YELLOW;
+const UNSELECTED: Color = Color::WHITE;
+const DIM: Color = Color::RGB(0x80, 0x80, 0x80);
+
+#[derive(Clone, Copy)]
+enum Mode {
+    Browse,
+    /// Waiting for the input to bind to the selected action.
+    Capture {
+        /// Binding already used by another action, bound anyway if it is pressed again.
+        conflict: Option<Binding>,
+        /// Whether the conflicting binding was let go since, so holding it does not count.
+        released: bool,
+    },
+}
+
+/// Whether `event` lets go of `binding`.
+fn releases(event: &Event, binding: Binding) -> bool {
+    match (event, binding) {
+        (Event::KeyUp { keycode: Some(keycode), .. }, Binding::Key(bound)) => *keycode == bound,
+        (Event::ControllerButtonUp { button, .. }, Binding::Button(bound)) => *button == bound,
+        (Event::ControllerAxisMotion { axis, value, .. }, Binding::Axis(bound, _)) => {
+            *axis == bound && value.unsigned_abs() < AXIS_THRESHOLD as u16
+        },
+        _ => false
+    }
+}
+
+/// Lists the app's actions and binds new keys, buttons or stick directions to them,
+/// saving every change to the app's bindings file.
+///
+/// Navigation uses fixed keys and the d-pad rather than the actions themselves, so a bad
+/// binding can always be undone from here.
+pub struct Rebinding {
+    actions: Vec<Action>,
+    selected: usize,
+    mode: Mode,
+    status: String,
+    controllers: Controllers,
+    font: Option<Font>,
+}
+
+impl Rebinding {
+    pub fn boxed() -> Box<dyn Scene> {
+        Box::new(Rebinding {
+            actions: Vec::new(),
+            selected: 0,
+            mode: Mode::Browse,
+            status: String::new(),
+            controllers: Controllers::new(),
+            font: None,
+        })
+    }
+
+    fn selected_name(&self) -> String {
+        self.actions[self.selected].name.clone()
+    }
+
+    fn move_selection(&mut self, step: isize) {
+        let count = self.actions.len() as isize;
+        if count > 0 {
+            self.selected = (self.selected as isize + step).rem_euclid(count) as usize;
+        }
+    }
+
+    /// Saves the app's bindings after a change and takes a copy of them to draw.
+    fn changed(&mut self, app: &mut App, status: String) {
+        self.status = status;
+        if let Err(error) = app.save_bindings() {
+            println!("Warning: {}", error);
+            self.status = error.to_string();
+        }
+        self.actions = app.actions().actions().to_vec();
+    }
+
+    fn browse(&mut self, app: &mut App, event: &Event) {
+        match *event {
+            Event::KeyDown { keycode: Some(keycode), keymod, .. } => match keycode {
+                Keycode::Up => self.move_selection(-1),
+                Keycode::Down => self.move_selection(1),
+                Keycode::Return | Keycode::KpEnter => self.start_capture(),
+                Keycode::Delete => {
+                    let name = self.selected_name();
+                    app.actions_mut().set(&name, Vec::new());
+                    self.changed(app, format!("Cleared {}", name));
+                },
+                Keycode::R if keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD) => {
+                    app.actions_mut().reset_all();
+                    self.changed(app, "Reset every action".to_string());
+                },
+                Keycode::R => {
+                    let name = self.selected_name();
+                    app.actions_mut().reset(&name);
+                    self.changed(app, format!("Reset {}", name));
+                },
+                _ => ()
+            },
+            Event::ControllerButtonDown { button, .. } => match button {
+                Button::DPadUp => self.move_selection(-1),
+                Button::DPadDown => self.move_selection(1),
+                Button::A => self.start_capture(),
+                _ => ()
+            },
+            _ => ()
+        }
+    }
+
+    fn start_capture(&mut self) {
+        if self.actions.is_empty() {
+            return;
+        }
+        self.mode = Mode::Capture { conflict: None, released: false };
+        self.status = format!("Press a key, button or stick direction for {} (Escape to cancel)", self.selected_name());
+    }
+
+    fn capture(&mut self, app: &mut App, event: &Event) {
+        if let Event::KeyDown { keycode: Some(Keycode::Escape), .. } = *event {
+            self.mode = Mode::Browse;
+            self.status = "Cancelled".to_string();
+            return;
+        }
+
+        let (conflict, mut released) = match self.mode {
+            Mode::Browse => return,
+            Mode::Capture { conflict, released } => (conflict, released)
+        };
+        if let Some(pending) = conflict {
+            if releases(event, pending) {
+                released = true;
+                self.mode = Mode::Capture { conflict, released };
+            }
+        }
+
+        let binding = match Binding::from_event(event) {
+            None => return,
+            Some(binding) => binding
+        };
+        if let Event::KeyDown { repeat: true, .. } = *event {
+            return;
+        }
+
+        let name = self.selected_name();
+        let others: Vec<String> = app.actions().bound_to(binding).into_iter()
+            .filter(|&other| other != name)
+            .map(str::to_string)
+            .collect();
+
+        if app.actions().bindings(&name).contains(&binding) {
+            self.mode = Mode::Browse;
+            self.status = format!("{} is already bound to {}", binding, name);
+        } else if !others.is_empty() && (conflict != Some(binding) || !released) {
+            if conflict != Some(binding) {
+                self.mode = Mode::Capture { conflict: Some(binding), released: false };
+                self.status = format!("{} is used by {}: press it again to move it here", binding, others.join(", "));
+            }
+        } else {
+            app.actions_mut().unbind(binding);
+            let mut bindings = app.actions().bindings(&name).to_vec();
+            bindings.push(binding);
+            app.actions_mut().set(&name, bindings);
+
+            self.mode = Mode::Browse;
+            self.changed(app, format!("Bound {} to {}", binding, name));
+        }
+    }
+}
+
+impl Scene for Rebinding {
+    fn on_enter(&mut self, app: &mut App) -> Result<(), Error> {
+        self.font = Some(Font::builtin()?);
+        self.actions = app.actions().actions().to_vec();
+        self.status = match app.bindings_path() {
+            None => "Changes last until the app closes".to_string(),
+            Some(path) => format!("Changes are saved to {}", path.display())
+        };
+
+        self.controllers.open_all(app)?;
+
+        Ok(())
+    }
+
+    fn on_exit(&mut self, _app: &mut App) -> Result<(), Error> {
+        self.controllers.close_all();
+        Ok(())
+    }
+
+    fn on_event(&mut self, app: &mut App, event: &Event) -> Result<Transition, Error> {
+        self.controllers.handle_event(app, event)?;
+
+        match self.mode {
+            Mode::Browse => self.browse(app, event),
+            Mode::Capture { .. } => self.capture(app, event)
+        }
+
+        Ok(Transition::Stay)
+    }
+
+    fn render(&mut self, canvas: &mut WindowCanvas, textures: &mut Textures) -> Result<(), Error> {
+        canvas.set_draw_color(BACKGROUND);
+        canvas.clear();
+
+        let font = match &self.font {
+            None => return Ok(()),
+            Some(font) => font
+        };
+        let mut font = textures.font(font)?;
+        let center = SCREEN_WIDTH as i32 / 2;
+
+        font.draw(canvas, TITLE, center, 16, &TextStyle::new(Color::WHITE).scale(3).align(Align::Center))?;
+
+        let wrap = SCREEN_WIDTH - BINDINGS_LEFT as u32 - 16;
+        for (index, action) in self.actions.iter().enumerate() {
+            let y = LIST_TOP + index as i32 * LINE_HEIGHT;
+            let color = if index == self.selected { SELECTED } else { UNSELECTED };
+            font.draw(canvas, &action.name, 48, y, &TextStyle::new(color).scale(2))?;
+
+            let (bindings, color) = if action.bindings.is_empty() {
+                ("(unbound)".to_string(), DIM)
+            } else {
+                let bindings: Vec<String> = action.bindings.iter().map(Binding::to_string).collect();
+                (bindings.join(", "), color)
+            };
+            font.draw(canvas, &bindings, BINDINGS_LEFT, y + 4, &TextStyle::new(color).wrap(wrap))?;
+        }
+
+        let status_style = TextStyle::new(SELECTED).align(Align::Center).wrap(SCREEN_WIDTH - 16);
+        font.draw(canvas, &self.status, center, 408, &status_style)?;
+        font.draw(canvas, HELP, center, 448, &TextStyle::new(DIM).align(Align::Center))?;
+        Ok(())
+    }
+
+    /// Escape cancels a capture rather than leaving the lesson.
+    fn wants_escape(&self) -> bool {
+        matches!(self.mode, Mode::Capture { .. })
+    }
+}
+
+pub fn run(options: &Options) -> Result<(), Error> {
+    scene::run(options, vec![Rebinding::boxed()])
+}
@@ -0,0 +1,256 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+use sdl2::event::Event;
+use sdl2::image::InitFlag;
+use sdl2::keyboard::Keycode;
+use sdl2::render::WindowCanvas;
+use crate::Error;
+use crate::lazy_foo::app::{App, AppBuilder, Options};
+use crate::lazy_foo::assets::{Assets, TextureCache, TextureHandle};
+use crate::lazy_foo::text::{Font, FontTexture};
+#[cfg(feature = "ttf")]
+use sdl2::pixels::Color;
+#[cfg(feature = "ttf")]
+use sdl2::render::Texture;
+#[cfg(feature = "ttf")]
+use sdl2::ttf::GlyphMetrics;
+#[cfg(feature = "ttf")]
+use crate::lazy_foo::ttf::{Fonts, RenderMode};
+
+/// Builds a boxed scene, so lessons can list theirs without creating it.
+pub type SceneFn = fn() -> Box<dyn Scene>;
+
+/// What the scene stack should do after a scene handled an event or an update.
+pub enum Transition {
+    Stay,
+    /// Covers the current scene with a new one.
+    Push(Box<dyn Scene>),
+    /// Leaves the current scene, uncovering the one below it.
+    Pop,
+    /// Leaves the current scene for a new one.
+    Replace(Box<dyn Scene>),
+    /// Leaves every scene.
+    Quit,
+}
+
+/// One screen of the app: a lesson, a menu, ...
+///
+/// A scene is entered when it is pushed on the stack and exited when it is removed from it;
+/// a scene covered by another one stays entered but receives nothing until it is on top again.
+pub trait Scene {
+    fn on_enter(&mut self, _app: &mut App) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn on_exit(&mut self, _app: &mut App) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn on_event(&mut self, app: &mut App, event: &Event) -> Result<Transition, Error>;
+
+    /// Advances the scene by one fixed tick of length `dt`.
+    fn update(&mut self, _app: &mut App, _dt: Duration) -> Result<Transition, Error> {
+        Ok(Transition::Stay)
+    }
+
+    /// Called once a frame between the updates and [`Scene::render`], to read what only
+    /// holds for this frame, like [`App::alpha`] to interpolate with or [`App::stats`].
+    fn before_render(&mut self, _app: &App) {}
+
+    fn render(&mut self, canvas: &mut WindowCanvas, textures: &mut Textures) -> Result<(), Error>;
+
+    /// Whether Escape goes to [`Scene::on_event`] right now instead of leaving the scene.
+    fn wants_escape(&self) -> bool {
+        false
+    }
+}
+
+/// Textures a scene can draw, created from the app's assets on first use.
+pub struct Textures<'a, 't> {
+    assets: &'a mut Assets,
+    cache: &'a mut TextureCache<'t>,
+    #[cfg(feature = "ttf")]
+    fonts: &'a mut Fonts<'t>,
+}
+
+impl<'a, 't> Textures<'a, 't> {
+    pub fn texture(&mut self, path: &str) -> Result<TextureHandle<'t>, Error> {
+        self.cache.texture(self.assets, path)
+    }
+
+    /// `font`, ready to draw; its atlas is only uploaded the first time.
+    pub fn font<'f>(&'f mut self, font: &'f Font) -> Result<FontTexture<'f, 't>, Error> {
+        font.texture(self.cache)
+    }
+
+    /// `text` in the TrueType font at `path`, rendered on first use; see [`TextCache::text`].
+    ///
+    /// [`TextCache::text`]: crate::lazy_foo::ttf::TextCache::text
+    #[cfg(feature = "ttf")]
+    pub fn text(&mut self, path: &str, text: &str, size: u16, mode: RenderMode, color: Color)
+        -> Result<&mut Texture<'t>, Error>
+    {
+        self.fonts.cache(path).text(self.assets, self.cache, text, size, mode, color)
+    }
+
+    /// Metrics of `character` in the TrueType font at `path`, if it has a glyph for it.
+    #[cfg(feature = "ttf")]
+    pub fn glyph_metrics(&mut self, path: &str, size: u16, character: char)
+        -> Result<Option<GlyphMetrics>, Error>
+    {
+        self.fonts.cache(path).family().glyph_metrics(self.assets, size, character)
+    }
+}
+
+/// Scenes stacked on top of each other; only the top one is active.
+#[derive(Default)]
+pub struct SceneStack {
+    scenes: Vec<Box<dyn Scene>>,
+}
+
+impl SceneStack {
+    pub fn new() -> SceneStack {
+        SceneStack::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.scenes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scenes.is_empty()
+    }
+
+    pub fn push(&mut self, app: &mut App, mut scene: Box<dyn Scene>) -> Result<(), Error> {
+        scene.on_enter(app)?;
+        self.scenes.push(scene);
+        Ok(())
+    }
+
+    pub fn pop(&mut self, app: &mut App) -> Result<Option<Box<dyn Scene>>, Error> {
+        match self.scenes.pop() {
+            None => Ok(None),
+            Some(mut scene) => {
+                scene.on_exit(app)?;
+                Ok(Some(scene))
+            }
+        }
+    }
+
+    pub fn replace(&mut self, app: &mut App, scene: Box<dyn Scene>) -> Result<(), Error> {
+        self.pop(app)?;
+        self.push(app, scene)
+    }
+
+    pub fn apply(&mut self, app: &mut App, transition: Transition) -> Result<(), Error> {
+        match transition {
+            Transition::Stay => Ok(()),
+            Transition::Push(scene) => self.push(app, scene),
+            Transition::Pop => self.pop(app).map(|_| ()),
+            Transition::Replace(scene) => self.replace(app, scene),
+            Transition::Quit => {
+                while self.pop(app)?.is_some() {}
+                Ok(())
+            }
+        }
+    }
+
+    pub fn on_event(&mut self, app: &mut App, event: &Event) -> Result<(), Error> {
+        let transition = match self.scenes.last_mut() {
+            None => return Ok(()),
+            Some(scene) => scene.on_event(app, event)?
+        };
+        self.apply(app, transition)
+    }
+
+    pub fn update(&mut self, app: &mut App, dt: Duration) -> Result<(), Error> {
+        let transition = match self.scenes.last_mut() {
+            None => return Ok(()),
+            Some(scene) => scene.update(app, dt)?
+        };
+        self.apply(app, transition)
+    }
+
+    pub fn before_render(&mut self, app: &App) {
+        if let Some(scene) = self.scenes.last_mut() {
+            scene.before_render(app);
+        }
+    }
+
+    pub fn render(&mut self, canvas: &mut WindowCanvas, textures: &mut Textures) -> Result<(), Error> {
+        match self.scenes.last_mut() {
+            None => Ok(()),
+            Some(scene) => scene.render(canvas, textures)
+        }
+    }
+
+    pub fn wants_escape(&self) -> bool {
+        self.scenes.last().is_some_and(|scene| scene.wants_escape())
+    }
+}
+
+/// Runs `scenes` one after the other in a single window.
+///
+/// Each scene runs until it leaves the stack; Escape leaves it too when there is a scene
+/// to go back or on to, unless the scene wants Escape for itself. Quitting the window ends
+/// every scene at once.
+pub fn run(options: &Options, scenes: Vec<Box<dyn Scene>>) -> Result<(), Error> {
+    let mut app = AppBuilder::new("SDL Tutorial")
+        .options(options)
+        .hint("SDL_RENDER_SCALE_QUALITY", "1")
+        .accelerated()
+        .image(InitFlag::PNG)
+        .build()?;
+
+    // Fonts and textures share a lifetime, so SDL_ttf has to outlive the textures too.
+    #[cfg(feature = "ttf")]
+    let ttf = app.ttf()?;
+    let texture_creator = app.texture_creator();
+    let mut textures = TextureCache::new(&texture_creator);
+    #[cfg(feature = "ttf")]
+    let mut fonts = Fonts::new(&ttf);
+
+    let mut queue = VecDeque::from(scenes);
+    let mut stack = SceneStack::new();
+
+    'running: while app.next_frame()? {
+        if stack.is_empty() {
+            match queue.pop_front() {
+                None => break 'running,
+                Some(scene) => stack.push(&mut app, scene)?
+            }
+        }
+
+        for event in app.poll_events()? {
+            match event {
+                Event::Quit {..} => {
+                    stack.apply(&mut app, Transition::Quit)?;
+                    break 'running;
+                },
+                Event::KeyDown { keycode: Some(Keycode::Escape), .. }
+                    if (stack.len() > 1 || !queue.is_empty()) && !stack.wants_escape() => {
+                        stack.pop(&mut app).map(|_| ())?
+                    },
+                _ => stack.on_event(&mut app, &event)?
+            }
+        }
+
+        for _ in 0..app.ticks() {
+            let dt = app.tick();
+            stack.update(&mut app, dt)?;
+        }
+
+        stack.before_render(&app);
+        let (canvas, assets) = app.canvas_and_assets();
+        let mut scene_textures = Textures {
+            assets,
+            cache: &mut textures,
+            #[cfg(feature = "ttf")]
+            fonts: &mut fonts,
+        };
+        stack.render(canvas, &mut scene_textures)?;
+        app.present()?;
+    }
+
+    stack.apply(&mut app, Transition::Quit)
+}
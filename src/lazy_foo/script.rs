@@ -0,0 +1,506 @@
+use std::error;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use sdl2::controller::{Axis, Button};
+use sdl2::event::{Event, EventSender, WindowEvent};
+use sdl2::joystick::HatState;
+use sdl2::keyboard::{Keycode, Mod, Scancode};
+use sdl2::mouse::{MouseButton, MouseState, MouseWheelDirection};
+use crate::Error;
+use crate::lazy_foo::virtual_controller::VirtualController;
+
+/// Why an event script could not be loaded.
+#[derive(Debug)]
+pub enum ScriptError {
+    Io(io::Error),
+    Parse { line: usize, message: String },
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScriptError::Io(error) => write!(f, "{}", error),
+            ScriptError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl error::Error for ScriptError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            ScriptError::Io(error) => Some(error),
+            ScriptError::Parse { .. } => None,
+        }
+    }
+}
+
+/// An event to push at the start of `frame` (1-based, as counted by `App::next_frame`).
+#[derive(Debug, Clone)]
+pub struct ScriptedEvent {
+    pub frame: u32,
+    pub event: Event,
+}
+
+/// A list of events to feed a lesson instead of a human.
+///
+/// Scripts are text files with one event per line, `#` starting a comment when it comes
+/// before the event type:
+///
+/// ```text
+/// # frame  event                    arguments
+/// 1        keydown                  Left
+/// 3        keyup                    Left
+/// 4        controllerbuttondown     0 a
+/// 5        controlleraxismotion     0 leftx -32768
+/// 9        quit
+/// ```
+///
+/// Key names are SDL key names (`Left`, `Space`, `Left Shift`...), controller buttons and
+/// axes use the GameControllerDB names (`a`, `dpup`, `leftx`...), and the number before
+/// them is the controller's joystick instance id, or the number of a virtual controller
+/// when the app has attached some (see [`ScriptPlayer::play`]). Mouse and window events are supported
+/// as well; see [`format_event`] for every event type and its arguments. Replays written
+/// by [`Recorder`] use the same format.
+#[derive(Debug, Clone, Default)]
+pub struct Script {
+    events: Vec<ScriptedEvent>,
+}
+
+impl Script {
+    pub fn new(mut events: Vec<ScriptedEvent>) -> Script {
+        events.sort_by_key(|scripted| scripted.frame);
+        Script { events }
+    }
+
+    pub fn parse(text: &str) -> Result<Script, ScriptError> {
+        let mut events = Vec::new();
+
+        for (index, line) in text.lines().enumerate() {
+            let line = strip_comment(line);
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let event = parse_line(line)
+                .map_err(|message| ScriptError::Parse { line: index + 1, message })?;
+            events.push(event);
+        }
+
+        Ok(Script::new(events))
+    }
+
+    pub fn load(path: &Path) -> Result<Script, Error> {
+        let script_error = |source| Error::Script { path: path.display().to_string(), source };
+
+        let text = fs::read_to_string(path).map_err(|error| script_error(ScriptError::Io(error)))?;
+        Script::parse(&text).map_err(script_error)
+    }
+
+    pub fn events(&self) -> &[ScriptedEvent] {
+        &self.events
+    }
+}
+
+/// Pushes the events of a [`Script`] into SDL's queue as their frames come up.
+pub struct ScriptPlayer {
+    script: Script,
+    next: usize,
+}
+
+impl ScriptPlayer {
+    pub fn new(script: Script) -> ScriptPlayer {
+        ScriptPlayer { script, next: 0 }
+    }
+
+    /// Pushes every event due on or before `frame`.
+    ///
+    /// Controller button and axis events for controller `n` are played on `virtual_controllers[n]`
+    /// instead when there is one, so SDL reports them like it would for a real pad.
+    pub fn play(&mut self, frame: u32, sender: &EventSender, virtual_controllers: &[VirtualController])
+        -> Result<(), Error>
+    {
+        while let Some(scripted) = self.script.events.get(self.next) {
+            if scripted.frame > frame {
+                break;
+            }
+
+            let virtual_controller = match scripted.event {
+                Event::ControllerButtonDown { which, .. }
+                | Event::ControllerButtonUp { which, .. }
+                | Event::ControllerAxisMotion { which, .. } => virtual_controllers.get(which as usize),
+                _ => None
+            };
+            match (virtual_controller, &scripted.event) {
+                (Some(pad), Event::ControllerButtonDown { button, .. }) => pad.press(*button)?,
+                (Some(pad), Event::ControllerButtonUp { button, .. }) => pad.release(*button)?,
+                (Some(pad), Event::ControllerAxisMotion { axis, value, .. }) => pad.set_axis(*axis, *value)?,
+                _ => sender.push_event(scripted.event.clone()).map_err(Error::sdl(Error::EventPush))?
+            }
+            self.next += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Skips every event due on or before `frame` without playing it, for a run that
+    /// continues one that already played them.
+    pub fn skip(&mut self, frame: u32) {
+        while self.script.events.get(self.next).is_some_and(|scripted| scripted.frame <= frame) {
+            self.next += 1;
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.script.events.len()
+    }
+}
+
+/// Writes the events a lesson receives to a replay file that [`Script::load`] can play back.
+pub struct Recorder {
+    path: PathBuf,
+    writer: BufWriter<File>,
+}
+
+impl Recorder {
+    pub fn create(path: &Path) -> Result<Recorder, Error> {
+        let file = File::create(path).map_err(|source| record_error(path, source))?;
+        let mut recorder = Recorder { path: path.to_path_buf(), writer: BufWriter::new(file) };
+
+        recorder.write_line("# sdl2_learn replay: <frame> <event> [arguments]")?;
+        Ok(recorder)
+    }
+
+    /// Carries on recording into the file at `path`, after the events already in it.
+    pub fn append(path: &Path) -> Result<Recorder, Error> {
+        let file = OpenOptions::new().append(true).create(true).open(path)
+            .map_err(|source| record_error(path, source))?;
+        Ok(Recorder { path: path.to_path_buf(), writer: BufWriter::new(file) })
+    }
+
+    /// Appends `event`, received during `frame`. Events that cannot be replayed are skipped.
+    pub fn record(&mut self, frame: u32, event: &Event) -> Result<(), Error> {
+        match format_event(event) {
+            None => Ok(()),
+            Some(line) => self.write_line(&format!("{} {}", frame, line))
+        }
+    }
+
+    pub fn flush(&mut self) -> Result<(), Error> {
+        self.writer.flush().map_err(|source| record_error(&self.path, source))
+    }
+
+    fn write_line(&mut self, line: &str) -> Result<(), Error> {
+        writeln!(self.writer, "{}", line).map_err(|source| record_error(&self.path, source))
+    }
+}
+
+fn record_error(path: &Path, source: io::Error) -> Error {
+    Error::Record { path: path.display().to_string(), source }
+}
+
+/// Formats `event` as a script line without its frame number, or `None` if SDL cannot
+/// push that kind of event back into its queue.
+///
+/// | event                        | arguments                          |
+/// |------------------------------|------------------------------------|
+/// | `quit`                       |                                    |
+/// | `keydown`, `keyup`           | key name                           |
+/// | `mousemotion`                | x y xrel yrel button-state         |
+/// | `mousebuttondown`, `...up`   | button clicks x y                  |
+/// | `mousewheel`                 | x y                                |
+/// | `window`                     | window event name [data1 data2]    |
+/// | `controllerbuttondown`, `...up` | which button                    |
+/// | `controlleraxismotion`       | which axis value                   |
+/// | `controllerdeviceadded`, `...removed`, `...remapped` | which      |
+/// | `joybuttondown`, `joybuttonup` | which button                     |
+/// | `joyaxismotion`              | which axis value                   |
+/// | `joyhatmotion`               | which hat state                    |
+pub fn format_event(event: &Event) -> Option<String> {
+    let line = match event {
+        Event::Quit { .. } => "quit".to_string(),
+        Event::KeyDown { keycode: Some(keycode), .. } => format!("keydown {}", keycode.name()),
+        Event::KeyUp { keycode: Some(keycode), .. } => format!("keyup {}", keycode.name()),
+        Event::MouseMotion { mousestate, x, y, xrel, yrel, .. } => {
+            format!("mousemotion {} {} {} {} {}", x, y, xrel, yrel, mousestate.to_sdl_state())
+        },
+        Event::MouseButtonDown { mouse_btn, clicks, x, y, .. } => {
+            format!("mousebuttondown {} {} {} {}", mouse_button_name(*mouse_btn)?, clicks, x, y)
+        },
+        Event::MouseButtonUp { mouse_btn, clicks, x, y, .. } => {
+            format!("mousebuttonup {} {} {} {}", mouse_button_name(*mouse_btn)?, clicks, x, y)
+        },
+        Event::MouseWheel { x, y, .. } => format!("mousewheel {} {}", x, y),
+        Event::Window { win_event, .. } => format!("window {}", format_window_event(win_event)?),
+        Event::ControllerButtonDown { which, button, .. } => {
+            format!("controllerbuttondown {} {}", which, button.string())
+        },
+        Event::ControllerButtonUp { which, button, .. } => {
+            format!("controllerbuttonup {} {}", which, button.string())
+        },
+        Event::ControllerAxisMotion { which, axis, value, .. } => {
+            format!("controlleraxismotion {} {} {}", which, axis.string(), value)
+        },
+        Event::ControllerDeviceAdded { which, .. } => format!("controllerdeviceadded {}", which),
+        Event::ControllerDeviceRemoved { which, .. } => format!("controllerdeviceremoved {}", which),
+        Event::ControllerDeviceRemapped { which, .. } => format!("controllerdeviceremapped {}", which),
+        Event::JoyButtonDown { which, button_idx, .. } => format!("joybuttondown {} {}", which, button_idx),
+        Event::JoyButtonUp { which, button_idx, .. } => format!("joybuttonup {} {}", which, button_idx),
+        Event::JoyAxisMotion { which, axis_idx, value, .. } => {
+            format!("joyaxismotion {} {} {}", which, axis_idx, value)
+        },
+        Event::JoyHatMotion { which, hat_idx, state, .. } => {
+            format!("joyhatmotion {} {} {}", which, hat_idx, state.to_raw())
+        },
+        _ => return None
+    };
+
+    Some(line)
+}
+
+const WINDOW_EVENTS: &[(&str, WindowEvent)] = &[
+    ("shown", WindowEvent::Shown),
+    ("hidden", WindowEvent::Hidden),
+    ("exposed", WindowEvent::Exposed),
+    ("minimized", WindowEvent::Minimized),
+    ("maximized", WindowEvent::Maximized),
+    ("restored", WindowEvent::Restored),
+    ("enter", WindowEvent::Enter),
+    ("leave", WindowEvent::Leave),
+    ("focusgained", WindowEvent::FocusGained),
+    ("focuslost", WindowEvent::FocusLost),
+    ("close", WindowEvent::Close),
+    ("takefocus", WindowEvent::TakeFocus),
+];
+
+fn format_window_event(event: &WindowEvent) -> Option<String> {
+    let line = match event {
+        WindowEvent::Moved(x, y) => format!("moved {} {}", x, y),
+        WindowEvent::Resized(width, height) => format!("resized {} {}", width, height),
+        WindowEvent::SizeChanged(width, height) => format!("sizechanged {} {}", width, height),
+        _ => {
+            let (name, _) = WINDOW_EVENTS.iter().find(|(_, known)| known == event)?;
+            name.to_string()
+        }
+    };
+
+    Some(line)
+}
+
+fn parse_window_event(args: &[&str]) -> Result<WindowEvent, String> {
+    let name = args.first().ok_or("missing window event")?;
+
+    let event = match *name {
+        "moved" | "resized" | "sizechanged" => {
+            expect_args(name, &args[1..], 2)?;
+            let (first, second) = (parse_number(args[1])?, parse_number(args[2])?);
+            match *name {
+                "moved" => WindowEvent::Moved(first, second),
+                "resized" => WindowEvent::Resized(first, second),
+                _ => WindowEvent::SizeChanged(first, second)
+            }
+        },
+        _ => {
+            expect_args(name, &args[1..], 0)?;
+            WINDOW_EVENTS.iter()
+                .find(|(known, _)| known == name)
+                .map(|(_, event)| *event)
+                .ok_or(format!("unknown window event '{}'", name))?
+        }
+    };
+
+    Ok(event)
+}
+
+const MOUSE_BUTTONS: &[(&str, MouseButton)] = &[
+    ("left", MouseButton::Left),
+    ("middle", MouseButton::Middle),
+    ("right", MouseButton::Right),
+    ("x1", MouseButton::X1),
+    ("x2", MouseButton::X2),
+];
+
+fn mouse_button_name(button: MouseButton) -> Option<&'static str> {
+    MOUSE_BUTTONS.iter().find(|(_, known)| *known == button).map(|(name, _)| *name)
+}
+
+fn parse_mouse_button(name: &str) -> Result<MouseButton, String> {
+    MOUSE_BUTTONS.iter()
+        .find(|(known, _)| *known == name)
+        .map(|(_, button)| *button)
+        .ok_or(format!("unknown mouse button '{}'", name))
+}
+
+/// `line` up to the `#` starting its comment, if any. Only a `#` starting a word before
+/// the event type does; after it, `#` is an argument such as the key name in `keydown #`.
+fn strip_comment(line: &str) -> &str {
+    for (index, _) in line.match_indices('#') {
+        let before = &line[..index];
+        let starts_word = before.is_empty() || before.ends_with(char::is_whitespace);
+        if starts_word && before.split_whitespace().count() < 2 {
+            return before;
+        }
+    }
+
+    line
+}
+
+fn parse_line(line: &str) -> Result<ScriptedEvent, String> {
+    let mut parts = line.split_whitespace();
+
+    let frame = parts.next().unwrap_or_default();
+    let frame = frame.parse()
+        .map_err(|_| format!("'{}' is not a frame number", frame))?;
+
+    let kind = parts.next().ok_or("missing event type")?;
+    let args: Vec<&str> = parts.collect();
+
+    let event = match kind {
+        "quit" => {
+            expect_args(kind, &args, 0)?;
+            Event::Quit { timestamp: 0 }
+        },
+        "keydown" | "keyup" => {
+            let name = args.join(" ");
+            let keycode = Keycode::from_name(&name)
+                .ok_or(format!("unknown key '{}'", name))?;
+            key_event(kind == "keydown", keycode)
+        },
+        "mousemotion" => {
+            expect_args(kind, &args, 5)?;
+            Event::MouseMotion {
+                timestamp: 0,
+                window_id: 0,
+                which: 0,
+                mousestate: MouseState::from_sdl_state(parse_number(args[4])?),
+                x: parse_number(args[0])?,
+                y: parse_number(args[1])?,
+                xrel: parse_number(args[2])?,
+                yrel: parse_number(args[3])?
+            }
+        },
+        "mousebuttondown" | "mousebuttonup" => {
+            expect_args(kind, &args, 4)?;
+            let mouse_btn = parse_mouse_button(args[0])?;
+            let clicks = parse_number(args[1])?;
+            let (x, y) = (parse_number(args[2])?, parse_number(args[3])?);
+            if kind == "mousebuttondown" {
+                Event::MouseButtonDown { timestamp: 0, window_id: 0, which: 0, mouse_btn, clicks, x, y }
+            } else {
+                Event::MouseButtonUp { timestamp: 0, window_id: 0, which: 0, mouse_btn, clicks, x, y }
+            }
+        },
+        "mousewheel" => {
+            expect_args(kind, &args, 2)?;
+            Event::MouseWheel {
+                timestamp: 0,
+                window_id: 0,
+                which: 0,
+                x: parse_number(args[0])?,
+                y: parse_number(args[1])?,
+                direction: MouseWheelDirection::Normal
+            }
+        },
+        "window" => Event::Window {
+            timestamp: 0,
+            window_id: 0,
+            win_event: parse_window_event(&args)?
+        },
+        "controllerbuttondown" | "controllerbuttonup" => {
+            expect_args(kind, &args, 2)?;
+            let which = parse_number(args[0])?;
+            let button = Button::from_string(args[1])
+                .ok_or(format!("unknown controller button '{}'", args[1]))?;
+            if kind == "controllerbuttondown" {
+                Event::ControllerButtonDown { timestamp: 0, which, button }
+            } else {
+                Event::ControllerButtonUp { timestamp: 0, which, button }
+            }
+        },
+        "controlleraxismotion" => {
+            expect_args(kind, &args, 3)?;
+            let which = parse_number(args[0])?;
+            let axis = Axis::from_string(args[1])
+                .ok_or(format!("unknown controller axis '{}'", args[1]))?;
+            let value = parse_number(args[2])?;
+            Event::ControllerAxisMotion { timestamp: 0, which, axis, value }
+        },
+        "controllerdeviceadded" | "controllerdeviceremoved" | "controllerdeviceremapped" => {
+            expect_args(kind, &args, 1)?;
+            let which = parse_number(args[0])?;
+            match kind {
+                "controllerdeviceadded" => Event::ControllerDeviceAdded { timestamp: 0, which },
+                "controllerdeviceremoved" => Event::ControllerDeviceRemoved { timestamp: 0, which },
+                _ => Event::ControllerDeviceRemapped { timestamp: 0, which }
+            }
+        },
+        "joybuttondown" | "joybuttonup" => {
+            expect_args(kind, &args, 2)?;
+            let which = parse_number(args[0])?;
+            let button_idx = parse_number(args[1])?;
+            if kind == "joybuttondown" {
+                Event::JoyButtonDown { timestamp: 0, which, button_idx }
+            } else {
+                Event::JoyButtonUp { timestamp: 0, which, button_idx }
+            }
+        },
+        "joyaxismotion" => {
+            expect_args(kind, &args, 3)?;
+            Event::JoyAxisMotion {
+                timestamp: 0,
+                which: parse_number(args[0])?,
+                axis_idx: parse_number(args[1])?,
+                value: parse_number(args[2])?
+            }
+        },
+        "joyhatmotion" => {
+            expect_args(kind, &args, 3)?;
+            Event::JoyHatMotion {
+                timestamp: 0,
+                which: parse_number(args[0])?,
+                hat_idx: parse_number(args[1])?,
+                state: HatState::from_raw(parse_number(args[2])?)
+            }
+        },
+        _ => return Err(format!("unknown event type '{}'", kind))
+    };
+
+    Ok(ScriptedEvent { frame, event })
+}
+
+fn key_event(down: bool, keycode: Keycode) -> Event {
+    let scancode = Scancode::from_keycode(keycode);
+
+    if down {
+        Event::KeyDown {
+            timestamp: 0,
+            window_id: 0,
+            keycode: Some(keycode),
+            scancode,
+            keymod: Mod::NOMOD,
+            repeat: false
+        }
+    } else {
+        Event::KeyUp {
+            timestamp: 0,
+            window_id: 0,
+            keycode: Some(keycode),
+            scancode,
+            keymod: Mod::NOMOD,
+            repeat: false
+        }
+    }
+}
+
+fn expect_args(kind: &str, args: &[&str], count: usize) -> Result<(), String> {
+    if args.len() == count {
+        Ok(())
+    } else {
+        Err(format!("{} expects {} arguments, got {}", kind, count, args.len()))
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(text: &str) -> Result<T, String> {
+    text.parse().map_err(|_| format!("'{}' is not a valid number", text))
+}
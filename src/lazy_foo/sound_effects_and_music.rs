@@ -0,0 +1,141 @@
+use crate::Error;
+use crate::lazy_foo::app::Options;
+
+#[cfg(feature = "mixer")]
+mod lesson {
+    use sdl2::event::Event;
+    use sdl2::keyboard::Keycode;
+    use sdl2::mixer::{Chunk, Music};
+    use sdl2::pixels::Color;
+    use sdl2::render::WindowCanvas;
+    use crate::Error;
+    use crate::lazy_foo::app::{App, Options, SCREEN_WIDTH};
+    use crate::lazy_foo::audio::{Mixer, MAX_VOLUME};
+    use crate::lazy_foo::scene::{self, Scene, Textures, Transition};
+    use crate::lazy_foo::text::{Align, Font, TextStyle};
+
+    const MUSIC_PATH: &str = "imgs/sound_effects_and_music/music.wav";
+    const HIGH_PATH: &str = "imgs/sound_effects_and_music/high.wav";
+    const MEDIUM_PATH: &str = "imgs/sound_effects_and_music/medium.wav";
+    const LOW_PATH: &str = "imgs/sound_effects_and_music/low.wav";
+    const SCRATCH_PATH: &str = "imgs/sound_effects_and_music/scratch.wav";
+
+    const INSTRUCTIONS: &[&str] = &[
+        "Up: high, Down: low",
+        "Left/Right: medium, panned",
+        "Any other key: scratch",
+        "9: play/pause music, 0: stop music",
+    ];
+
+    /// The open mixer and everything loaded into it.
+    struct Sounds {
+        // Fields drop in order, so the effects and music go before the mixer.
+        music: Music<'static>,
+        high: Chunk,
+        medium: Chunk,
+        low: Chunk,
+        scratch: Chunk,
+        mixer: Mixer,
+    }
+
+    impl Sounds {
+        fn load(app: &mut App) -> Result<Sounds, Error> {
+            let mixer = Mixer::open(app)?;
+
+            Ok(Sounds {
+                music: mixer.load_music(app.assets(), MUSIC_PATH)?,
+                high: mixer.load_effect(app.assets(), HIGH_PATH)?,
+                medium: mixer.load_effect(app.assets(), MEDIUM_PATH)?,
+                low: mixer.load_effect(app.assets(), LOW_PATH)?,
+                scratch: mixer.load_effect(app.assets(), SCRATCH_PATH)?,
+                mixer,
+            })
+        }
+    }
+
+    #[derive(Default)]
+    pub struct SoundEffectsAndMusic {
+        sounds: Option<Sounds>,
+        font: Option<Font>,
+    }
+
+    impl SoundEffectsAndMusic {
+        pub fn boxed() -> Box<dyn Scene> {
+            Box::<SoundEffectsAndMusic>::default()
+        }
+    }
+
+    impl Scene for SoundEffectsAndMusic {
+        fn on_enter(&mut self, app: &mut App) -> Result<(), Error> {
+            self.sounds = Some(Sounds::load(app)?);
+            self.font = Some(Font::builtin()?);
+            Ok(())
+        }
+
+        fn on_exit(&mut self, _app: &mut App) -> Result<(), Error> {
+            // Closes the audio device, so nothing keeps playing over the next lesson.
+            self.sounds = None;
+            Ok(())
+        }
+
+        fn on_event(&mut self, _app: &mut App, event: &Event) -> Result<Transition, Error> {
+            let sounds = match &self.sounds {
+                None => return Ok(Transition::Stay),
+                Some(sounds) => sounds
+            };
+            let mixer = &sounds.mixer;
+
+            // The same keys the key_presses lesson swaps its images with.
+            if let Event::KeyDown { keycode: Some(keycode), repeat: false, .. } = *event {
+                match keycode {
+                    Keycode::Up => { mixer.play(&sounds.high, MAX_VOLUME, 0.0)?; },
+                    Keycode::Down => { mixer.play(&sounds.low, MAX_VOLUME, 0.0)?; },
+                    Keycode::Left => { mixer.play(&sounds.medium, MAX_VOLUME, -1.0)?; },
+                    Keycode::Right => { mixer.play(&sounds.medium, MAX_VOLUME, 1.0)?; },
+                    Keycode::Num9 => mixer.toggle_music(&sounds.music)?,
+                    Keycode::Num0 => mixer.stop_music(),
+                    // Escape leaves the lesson when it was started from the menu.
+                    Keycode::Escape => (),
+                    _ => { mixer.play(&sounds.scratch, MAX_VOLUME / 2, 0.0)?; }
+                }
+            }
+
+            Ok(Transition::Stay)
+        }
+
+        fn render(&mut self, canvas: &mut WindowCanvas, textures: &mut Textures) -> Result<(), Error> {
+            canvas.set_draw_color(Color::WHITE);
+            canvas.clear();
+
+            let font = match &self.font {
+                None => return Ok(()),
+                Some(font) => font
+            };
+            let mut font = textures.font(font)?;
+            let center = SCREEN_WIDTH as i32 / 2;
+            let style = TextStyle::new(Color::BLACK).scale(2).align(Align::Center);
+            for (index, line) in INSTRUCTIONS.iter().enumerate() {
+                font.draw(canvas, line, center, 160 + index as i32 * 40, &style)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    pub fn run(options: &Options) -> Result<(), Error> {
+        scene::run(options, vec![SoundEffectsAndMusic::boxed()])
+    }
+}
+
+#[cfg(feature = "mixer")]
+pub use lesson::SoundEffectsAndMusic;
+
+#[cfg(feature = "mixer")]
+pub fn run(options: &Options) -> Result<(), Error> {
+    lesson::run(options)
+}
+
+#[cfg(not(feature = "mixer"))]
+pub fn run(_options: &Options) -> Result<(), Error> {
+    Err(Error::FeatureDisabled("mixer"))
+}
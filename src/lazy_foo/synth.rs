@@ -0,0 +1,262 @@
+//! A small synthesizer feeding SDL's audio callback directly, without SDL_mixer.
+
+use std::f32::consts::TAU;
+use std::time::Duration;
+use sdl2::audio::AudioCallback;
+
+/// Shape of an oscillator's wave, each going from -1.0 to 1.0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Waveform {
+    Sine,
+    Square,
+    Saw,
+    /// White noise; ignores the frequency.
+    Noise,
+}
+
+/// A waveform playing at some frequency.
+#[derive(Debug, Clone)]
+pub struct Oscillator {
+    waveform: Waveform,
+    frequency: f32,
+    /// Position in the current period, from 0.0 to 1.0.
+    phase: f32,
+    noise: u32,
+}
+
+impl Oscillator {
+    pub fn new(waveform: Waveform, frequency: f32) -> Oscillator {
+        Oscillator { waveform, frequency, phase: 0.0, noise: 0x2545_F491 }
+    }
+
+    /// Returns the next sample at `sample_rate` samples per second.
+    pub fn sample(&mut self, sample_rate: u32) -> f32 {
+        let value = match self.waveform {
+            Waveform::Sine => (self.phase * TAU).sin(),
+            Waveform::Square => if self.phase < 0.5 { 1.0 } else { -1.0 },
+            Waveform::Saw => 2.0 * self.phase - 1.0,
+            Waveform::Noise => {
+                // xorshift32: cheap, and the same noise on every run.
+                self.noise ^= self.noise << 13;
+                self.noise ^= self.noise >> 17;
+                self.noise ^= self.noise << 5;
+                self.noise as f32 / u32::MAX as f32 * 2.0 - 1.0
+            }
+        };
+
+        self.phase = (self.phase + self.frequency / sample_rate as f32).fract();
+        value
+    }
+}
+
+/// Attack, decay, sustain and release of a note's volume.
+///
+/// The volume rises to 1.0 over `attack`, falls to `sustain` over `decay`, stays there while
+/// the note is held, and falls to 0.0 over `release` once it is let go.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Envelope {
+    pub attack: Duration,
+    pub decay: Duration,
+    pub sustain: f32,
+    pub release: Duration,
+}
+
+impl Default for Envelope {
+    fn default() -> Envelope {
+        Envelope {
+            attack: Duration::from_millis(10),
+            decay: Duration::from_millis(100),
+            sustain: 0.7,
+            release: Duration::from_millis(200),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Stage {
+    Attack,
+    Decay,
+    Sustain,
+    Release { step: f32 },
+    Done,
+}
+
+/// Steps needed to cover `duration` at `sample_rate`, at least one.
+fn steps(duration: Duration, sample_rate: u32) -> f32 {
+    (duration.as_secs_f32() * sample_rate as f32).max(1.0)
+}
+
+/// One playing note: an oscillator shaped by an envelope.
+#[derive(Debug, Clone)]
+pub struct Voice {
+    key: u32,
+    oscillator: Oscillator,
+    envelope: Envelope,
+    gain: f32,
+    stage: Stage,
+    level: f32,
+}
+
+impl Voice {
+    pub fn new(key: u32, note: Note) -> Voice {
+        Voice {
+            key,
+            oscillator: Oscillator::new(note.waveform, note.frequency),
+            envelope: note.envelope,
+            gain: note.gain,
+            stage: Stage::Attack,
+            level: 0.0,
+        }
+    }
+
+    pub fn key(&self) -> u32 {
+        self.key
+    }
+
+    /// Current volume of the envelope, from 0.0 to 1.0.
+    pub fn level(&self) -> f32 {
+        self.level
+    }
+
+    /// Whether the note is still held, i.e. not released yet.
+    pub fn is_held(&self) -> bool {
+        !matches!(self.stage, Stage::Release { .. } | Stage::Done)
+    }
+
+    /// Whether the note has been released and faded out completely.
+    pub fn is_done(&self) -> bool {
+        self.stage == Stage::Done
+    }
+
+    /// Lets go of the note, fading it out from wherever its envelope is.
+    pub fn release(&mut self, sample_rate: u32) {
+        if self.is_held() {
+            let step = self.level / steps(self.envelope.release, sample_rate);
+            self.stage = Stage::Release { step };
+        }
+    }
+
+    pub fn sample(&mut self, sample_rate: u32) -> f32 {
+        let envelope = self.envelope;
+        match self.stage {
+            Stage::Attack => {
+                self.level += 1.0 / steps(envelope.attack, sample_rate);
+                if self.level >= 1.0 {
+                    self.level = 1.0;
+                    self.stage = Stage::Decay;
+                }
+            },
+            Stage::Decay => {
+                self.level -= (1.0 - envelope.sustain) / steps(envelope.decay, sample_rate);
+                if self.level <= envelope.sustain {
+                    self.level = envelope.sustain;
+                    self.stage = Stage::Sustain;
+                }
+            },
+            Stage::Sustain => self.level = envelope.sustain,
+            Stage::Release { step } => {
+                self.level -= step;
+                if self.level <= 0.0 {
+                    self.level = 0.0;
+                    self.stage = Stage::Done;
+                }
+            },
+            Stage::Done => return 0.0
+        }
+
+        self.oscillator.sample(sample_rate) * self.level * self.gain
+    }
+}
+
+/// What to play when a note starts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Note {
+    pub waveform: Waveform,
+    /// In Hz.
+    pub frequency: f32,
+    pub gain: f32,
+    pub envelope: Envelope,
+}
+
+impl Note {
+    pub fn new(waveform: Waveform, frequency: f32) -> Note {
+        Note { waveform, frequency, gain: 0.5, envelope: Envelope::default() }
+    }
+
+    /// The note `semitones` above (or below, when negative) A4 at 440 Hz.
+    pub fn from_a4(waveform: Waveform, semitones: i32) -> Note {
+        Note::new(waveform, 440.0 * 2f32.powf(semitones as f32 / 12.0))
+    }
+
+    pub fn gain(mut self, gain: f32) -> Note {
+        self.gain = gain;
+        self
+    }
+
+    pub fn envelope(mut self, envelope: Envelope) -> Note {
+        self.envelope = envelope;
+        self
+    }
+}
+
+/// Mixes every playing voice into mono samples.
+///
+/// Hand it to `AudioSubsystem::open_playback` to hear it, or call [`Synth::render`] to fill
+/// a buffer instead of a device.
+pub struct Synth {
+    sample_rate: u32,
+    volume: f32,
+    voices: Vec<Voice>,
+}
+
+impl Synth {
+    pub fn new(sample_rate: u32) -> Synth {
+        Synth { sample_rate, volume: 1.0, voices: Vec::new() }
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn set_volume(&mut self, volume: f32) {
+        self.volume = volume.max(0.0);
+    }
+
+    pub fn voices(&self) -> &[Voice] {
+        &self.voices
+    }
+
+    /// Starts `note` under `key`, restarting whatever was playing under that key.
+    pub fn note_on(&mut self, key: u32, note: Note) {
+        self.voices.retain(|voice| voice.key != key);
+        self.voices.push(Voice::new(key, note));
+    }
+
+    /// Releases the note playing under `key`, if any.
+    pub fn note_off(&mut self, key: u32) {
+        for voice in self.voices.iter_mut().filter(|voice| voice.key == key) {
+            voice.release(self.sample_rate);
+        }
+    }
+
+    /// Fills `out` with the next samples, clipped to -1.0..=1.0.
+    pub fn render(&mut self, out: &mut [f32]) {
+        for sample in out.iter_mut() {
+            let mut mixed = 0.0;
+            for voice in &mut self.voices {
+                mixed += voice.sample(self.sample_rate);
+            }
+            *sample = (mixed * self.volume).clamp(-1.0, 1.0);
+        }
+
+        self.voices.retain(|voice| !voice.is_done());
+    }
+}
+
+impl AudioCallback for Synth {
+    type Channel = f32;
+
+    fn callback(&mut self, out: &mut [f32]) {
+        self.render(out);
+    }
+}
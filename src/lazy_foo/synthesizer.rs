@@ -0,0 +1,180 @@
+use sdl2::audio::{AudioDevice, AudioSpecDesired};
+use sdl2::controller::Button;
+use sdl2::event::Event;
+use sdl2::keyboard::Keycode;
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::WindowCanvas;
+use crate::Error;
+use crate::lazy_foo::app::{App, Options, SCREEN_WIDTH};
+use crate::lazy_foo::controllers::Controllers;
+use crate::lazy_foo::scene::{self, Scene, Textures, Transition};
+use crate::lazy_foo::synth::{Note, Synth, Waveform};
+use crate::lazy_foo::text::{Align, Font, TextStyle};
+
+const SAMPLE_RATE: i32 = 44100;
+const HELP: &str = "Arrows or A/B/X/Y to play, 1-4 to change the wave";
+
+const WAVEFORMS: [(Keycode, Waveform, &str); 4] = [
+    (Keycode::Num1, Waveform::Sine, "sine"),
+    (Keycode::Num2, Waveform::Square, "square"),
+    (Keycode::Num3, Waveform::Saw, "saw"),
+    (Keycode::Num4, Waveform::Noise, "noise"),
+];
+
+/// One pad per direction, in the same place on the keyboard's arrows and the controller's
+/// face buttons: semitones from A4 and where the pad is drawn.
+const PADS: [(i32, i32, i32); 4] = [
+    (7, 0, -1),
+    (3, 1, 0),
+    (0, 0, 1),
+    (-5, -1, 0),
+];
+
+const PAD_SIZE: u32 = 80;
+const BACKGROUND: Color = Color::RGB(0x10, 0x10, 0x30);
+const PAD_UP: Color = Color::RGB(0x40, 0x40, 0x70);
+const PAD_DOWN: Color = Color::RGB(0xFF, 0xC0, 0x40);
+
+fn key_pad(keycode: Keycode) -> Option<usize> {
+    match keycode {
+        Keycode::Up => Some(0),
+        Keycode::Right => Some(1),
+        Keycode::Down => Some(2),
+        Keycode::Left => Some(3),
+        _ => None
+    }
+}
+
+fn button_pad(button: Button) -> Option<usize> {
+    match button {
+        Button::Y => Some(0),
+        Button::B => Some(1),
+        Button::A => Some(2),
+        Button::X => Some(3),
+        _ => None
+    }
+}
+
+/// Plays a note while an arrow key or a controller face button is held.
+pub struct Synthesizer {
+    device: Option<AudioDevice<Synth>>,
+    controllers: Controllers,
+    waveform: usize,
+    held: [bool; 4],
+    font: Option<Font>,
+}
+
+impl Synthesizer {
+    pub fn boxed() -> Box<dyn Scene> {
+        Box::new(Synthesizer { device: None, controllers: Controllers::new(), waveform: 0, held: [false; 4], font: None })
+    }
+
+    fn press(&mut self, pad: usize) {
+        if self.held[pad] {
+            return;
+        }
+        self.held[pad] = true;
+
+        let (semitones, ..) = PADS[pad];
+        let note = Note::from_a4(WAVEFORMS[self.waveform].1, semitones).gain(0.3);
+        if let Some(device) = &mut self.device {
+            device.lock().note_on(pad as u32, note);
+        }
+    }
+
+    fn release(&mut self, pad: usize) {
+        self.held[pad] = false;
+        if let Some(device) = &mut self.device {
+            device.lock().note_off(pad as u32);
+        }
+    }
+}
+
+impl Scene for Synthesizer {
+    fn on_enter(&mut self, app: &mut App) -> Result<(), Error> {
+        self.font = Some(Font::builtin()?);
+
+        let desired_spec = AudioSpecDesired {
+            freq: Some(SAMPLE_RATE),
+            channels: Some(1),
+            samples: Some(512),
+        };
+        let device = app.audio()?
+            .open_playback(None, &desired_spec, |spec| Synth::new(spec.freq as u32))
+            .map_err(Error::sdl(Error::AudioDevice))?;
+        device.resume();
+        self.device = Some(device);
+
+        // Playing with a controller is optional here.
+        self.controllers.open_all(app)?;
+
+        Ok(())
+    }
+
+    fn on_exit(&mut self, _app: &mut App) -> Result<(), Error> {
+        self.device = None;
+        self.controllers.close_all();
+        Ok(())
+    }
+
+    fn on_event(&mut self, app: &mut App, event: &Event) -> Result<Transition, Error> {
+        self.controllers.handle_event(app, event)?;
+
+        match *event {
+            Event::KeyDown { keycode: Some(keycode), .. } => {
+                if let Some(pad) = key_pad(keycode) {
+                    self.press(pad);
+                } else if let Some(index) = WAVEFORMS.iter().position(|&(key, ..)| key == keycode) {
+                    self.waveform = index;
+                }
+            },
+            Event::KeyUp { keycode: Some(keycode), .. } => {
+                if let Some(pad) = key_pad(keycode) {
+                    self.release(pad);
+                }
+            },
+            Event::ControllerButtonDown { button, .. } => {
+                if let Some(pad) = button_pad(button) {
+                    self.press(pad);
+                }
+            },
+            Event::ControllerButtonUp { button, .. } => {
+                if let Some(pad) = button_pad(button) {
+                    self.release(pad);
+                }
+            },
+            _ => ()
+        }
+
+        Ok(Transition::Stay)
+    }
+
+    fn render(&mut self, canvas: &mut WindowCanvas, textures: &mut Textures) -> Result<(), Error> {
+        canvas.set_draw_color(BACKGROUND);
+        canvas.clear();
+
+        let center = SCREEN_WIDTH as i32 / 2;
+        for (pad, &(_, column, row)) in PADS.iter().enumerate() {
+            let color = if self.held[pad] { PAD_DOWN } else { PAD_UP };
+            let x = center + column * (PAD_SIZE as i32 + 16) - PAD_SIZE as i32 / 2;
+            let y = 240 + row * (PAD_SIZE as i32 + 16) - PAD_SIZE as i32 / 2;
+            canvas.set_draw_color(color);
+            canvas.fill_rect(Rect::new(x, y, PAD_SIZE, PAD_SIZE)).map_err(Error::sdl(Error::Render))?;
+        }
+
+        let font = match &self.font {
+            None => return Ok(()),
+            Some(font) => font
+        };
+        let mut font = textures.font(font)?;
+        let wave = format!("Wave: {}", WAVEFORMS[self.waveform].2);
+        font.draw(canvas, &wave, center, 48, &TextStyle::new(Color::WHITE).scale(2).align(Align::Center))?;
+        font.draw(canvas, HELP, center, 440, &TextStyle::new(Color::WHITE).align(Align::Center))?;
+        Ok(())
+    }
+}
+
+pub fn run(options: &Options) -> Result<(), Error> {
+    scene::run(options, vec![Synthesizer::boxed()])
+}
@@ -0,0 +1,640 @@
+use std::collections::HashMap;
+use std::error;
+use std::fmt;
+use std::io::{self, Read};
+use std::path::Path;
+use sdl2::pixels::{Color, PixelFormatEnum};
+use sdl2::rect::{Point, Rect};
+use sdl2::render::{BlendMode, Texture, WindowCanvas};
+use sdl2::surface::{Surface, SurfaceRef};
+use crate::Error;
+use crate::lazy_foo::assets::{Assets, TextureCache};
+
+/// Width and height of a glyph of the built-in font.
+pub const GLYPH_SIZE: u32 = 8;
+
+/// Name the built-in font is reported under in errors.
+const BUILTIN: &str = "built-in font";
+
+/// The built-in 8x8 font, covering printable ASCII from `' '` to `'~'`.
+///
+/// Each glyph is eight rows, top to bottom; the lowest bit of a row is its leftmost pixel.
+/// Based on the public domain font8x8 by Daniel Hepper.
+const GLYPHS: [[u8; 8]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // ' '
+    [0x18, 0x3C, 0x3C, 0x18, 0x18, 0x00, 0x18, 0x00], // '!'
+    [0x36, 0x36, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // '"'
+    [0x36, 0x36, 0x7F, 0x36, 0x7F, 0x36, 0x36, 0x00], // '#'
+    [0x0C, 0x3E, 0x03, 0x1E, 0x30, 0x1F, 0x0C, 0x00], // '$'
+    [0x00, 0x63, 0x33, 0x18, 0x0C, 0x66, 0x63, 0x00], // '%'
+    [0x1C, 0x36, 0x1C, 0x6E, 0x3B, 0x33, 0x6E, 0x00], // '&'
+    [0x06, 0x06, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00], // "'"
+    [0x18, 0x0C, 0x06, 0x06, 0x06, 0x0C, 0x18, 0x00], // '('
+    [0x06, 0x0C, 0x18, 0x18, 0x18, 0x0C, 0x06, 0x00], // ')'
+    [0x00, 0x66, 0x3C, 0xFF, 0x3C, 0x66, 0x00, 0x00], // '*'
+    [0x00, 0x0C, 0x0C, 0x3F, 0x0C, 0x0C, 0x00, 0x00], // '+'
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C, 0x06], // ','
+    [0x00, 0x00, 0x00, 0x3F, 0x00, 0x00, 0x00, 0x00], // '-'
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C, 0x00], // '.'
+    [0x60, 0x30, 0x18, 0x0C, 0x06, 0x03, 0x01, 0x00], // '/'
+    [0x3E, 0x63, 0x73, 0x7B, 0x6F, 0x67, 0x3E, 0x00], // '0'
+    [0x0C, 0x0E, 0x0C, 0x0C, 0x0C, 0x0C, 0x3F, 0x00], // '1'
+    [0x1E, 0x33, 0x30, 0x1C, 0x06, 0x33, 0x3F, 0x00], // '2'
+    [0x1E, 0x33, 0x30, 0x1C, 0x30, 0x33, 0x1E, 0x00], // '3'
+    [0x38, 0x3C, 0x36, 0x33, 0x7F, 0x30, 0x78, 0x00], // '4'
+    [0x3F, 0x03, 0x1F, 0x30, 0x30, 0x33, 0x1E, 0x00], // '5'
+    [0x1C, 0x06, 0x03, 0x1F, 0x33, 0x33, 0x1E, 0x00], // '6'
+    [0x3F, 0x33, 0x30, 0x18, 0x0C, 0x0C, 0x0C, 0x00], // '7'
+    [0x1E, 0x33, 0x33, 0x1E, 0x33, 0x33, 0x1E, 0x00], // '8'
+    [0x1E, 0x33, 0x33, 0x3E, 0x30, 0x18, 0x0E, 0x00], // '9'
+    [0x00, 0x0C, 0x0C, 0x00, 0x00, 0x0C, 0x0C, 0x00], // ':'
+    [0x00, 0x0C, 0x0C, 0x00, 0x00, 0x0C, 0x0C, 0x06], // ';'
+    [0x18, 0x0C, 0x06, 0x03, 0x06, 0x0C, 0x18, 0x00], // '<'
+    [0x00, 0x00, 0x3F, 0x00, 0x00, 0x3F, 0x00, 0x00], // '='
+    [0x06, 0x0C, 0x18, 0x30, 0x18, 0x0C, 0x06, 0x00], // '>'
+    [0x1E, 0x33, 0x30, 0x18, 0x0C, 0x00, 0x0C, 0x00], // '?'
+    [0x3E, 0x63, 0x7B, 0x7B, 0x7B, 0x03, 0x1E, 0x00], // '@'
+    [0x0C, 0x1E, 0x33, 0x33, 0x3F, 0x33, 0x33, 0x00], // 'A'
+    [0x3F, 0x66, 0x66, 0x3E, 0x66, 0x66, 0x3F, 0x00], // 'B'
+    [0x3C, 0x66, 0x03, 0x03, 0x03, 0x66, 0x3C, 0x00], // 'C'
+    [0x1F, 0x36, 0x66, 0x66, 0x66, 0x36, 0x1F, 0x00], // 'D'
+    [0x7F, 0x46, 0x16, 0x1E, 0x16, 0x46, 0x7F, 0x00], // 'E'
+    [0x7F, 0x46, 0x16, 0x1E, 0x16, 0x06, 0x0F, 0x00], // 'F'
+    [0x3C, 0x66, 0x03, 0x03, 0x73, 0x66, 0x7C, 0x00], // 'G'
+    [0x33, 0x33, 0x33, 0x3F, 0x33, 0x33, 0x33, 0x00], // 'H'
+    [0x1E, 0x0C, 0x0C, 0x0C, 0x0C, 0x0C, 0x1E, 0x00], // 'I'
+    [0x78, 0x30, 0x30, 0x30, 0x33, 0x33, 0x1E, 0x00], // 'J'
+    [0x67, 0x66, 0x36, 0x1E, 0x36, 0x66, 0x67, 0x00], // 'K'
+    [0x0F, 0x06, 0x06, 0x06, 0x46, 0x66, 0x7F, 0x00], // 'L'
+    [0x63, 0x77, 0x7F, 0x7F, 0x6B, 0x63, 0x63, 0x00], // 'M'
+    [0x63, 0x67, 0x6F, 0x7B, 0x73, 0x63, 0x63, 0x00], // 'N'
+    [0x1C, 0x36, 0x63, 0x63, 0x63, 0x36, 0x1C, 0x00], // 'O'
+    [0x3F, 0x66, 0x66, 0x3E, 0x06, 0x06, 0x0F, 0x00], // 'P'
+    [0x1E, 0x33, 0x33, 0x33, 0x3B, 0x1E, 0x38, 0x00], // 'Q'
+    [0x3F, 0x66, 0x66, 0x3E, 0x36, 0x66, 0x67, 0x00], // 'R'
+    [0x1E, 0x33, 0x07, 0x0E, 0x38, 0x33, 0x1E, 0x00], // 'S'
+    [0x3F, 0x2D, 0x0C, 0x0C, 0x0C, 0x0C, 0x1E, 0x00], // 'T'
+    [0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x3F, 0x00], // 'U'
+    [0x33, 0x33, 0x33, 0x33, 0x33, 0x1E, 0x0C, 0x00], // 'V'
+    [0x63, 0x63, 0x63, 0x6B, 0x7F, 0x77, 0x63, 0x00], // 'W'
+    [0x63, 0x63, 0x36, 0x1C, 0x1C, 0x36, 0x63, 0x00], // 'X'
+    [0x33, 0x33, 0x33, 0x1E, 0x0C, 0x0C, 0x1E, 0x00], // 'Y'
+    [0x7F, 0x63, 0x31, 0x18, 0x4C, 0x66, 0x7F, 0x00], // 'Z'
+    [0x1E, 0x06, 0x06, 0x06, 0x06, 0x06, 0x1E, 0x00], // '['
+    [0x03, 0x06, 0x0C, 0x18, 0x30, 0x60, 0x40, 0x00], // '\\'
+    [0x1E, 0x18, 0x18, 0x18, 0x18, 0x18, 0x1E, 0x00], // ']'
+    [0x08, 0x1C, 0x36, 0x63, 0x00, 0x00, 0x00, 0x00], // '^'
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF], // '_'
+    [0x0C, 0x0C, 0x18, 0x00, 0x00, 0x00, 0x00, 0x00], // '`'
+    [0x00, 0x00, 0x1E, 0x30, 0x3E, 0x33, 0x6E, 0x00], // 'a'
+    [0x07, 0x06, 0x06, 0x3E, 0x66, 0x66, 0x3B, 0x00], // 'b'
+    [0x00, 0x00, 0x1E, 0x33, 0x03, 0x33, 0x1E, 0x00], // 'c'
+    [0x38, 0x30, 0x30, 0x3E, 0x33, 0x33, 0x6E, 0x00], // 'd'
+    [0x00, 0x00, 0x1E, 0x33, 0x3F, 0x03, 0x1E, 0x00], // 'e'
+    [0x1C, 0x36, 0x06, 0x0F, 0x06, 0x06, 0x0F, 0x00], // 'f'
+    [0x00, 0x00, 0x6E, 0x33, 0x33, 0x3E, 0x30, 0x1F], // 'g'
+    [0x07, 0x06, 0x36, 0x6E, 0x66, 0x66, 0x67, 0x00], // 'h'
+    [0x0C, 0x00, 0x0E, 0x0C, 0x0C, 0x0C, 0x1E, 0x00], // 'i'
+    [0x30, 0x00, 0x30, 0x30, 0x30, 0x33, 0x33, 0x1E], // 'j'
+    [0x07, 0x06, 0x66, 0x36, 0x1E, 0x36, 0x67, 0x00], // 'k'
+    [0x0E, 0x0C, 0x0C, 0x0C, 0x0C, 0x0C, 0x1E, 0x00], // 'l'
+    [0x00, 0x00, 0x33, 0x7F, 0x7F, 0x6B, 0x63, 0x00], // 'm'
+    [0x00, 0x00, 0x1F, 0x33, 0x33, 0x33, 0x33, 0x00], // 'n'
+    [0x00, 0x00, 0x1E, 0x33, 0x33, 0x33, 0x1E, 0x00], // 'o'
+    [0x00, 0x00, 0x3B, 0x66, 0x66, 0x3E, 0x06, 0x0F], // 'p'
+    [0x00, 0x00, 0x6E, 0x33, 0x33, 0x3E, 0x30, 0x78], // 'q'
+    [0x00, 0x00, 0x3B, 0x6E, 0x66, 0x06, 0x0F, 0x00], // 'r'
+    [0x00, 0x00, 0x3E, 0x03, 0x1E, 0x30, 0x1F, 0x00], // 's'
+    [0x08, 0x0C, 0x3E, 0x0C, 0x0C, 0x2C, 0x18, 0x00], // 't'
+    [0x00, 0x00, 0x33, 0x33, 0x33, 0x33, 0x6E, 0x00], // 'u'
+    [0x00, 0x00, 0x33, 0x33, 0x33, 0x1E, 0x0C, 0x00], // 'v'
+    [0x00, 0x00, 0x63, 0x6B, 0x7F, 0x7F, 0x36, 0x00], // 'w'
+    [0x00, 0x00, 0x63, 0x36, 0x1C, 0x36, 0x63, 0x00], // 'x'
+    [0x00, 0x00, 0x33, 0x33, 0x33, 0x3E, 0x30, 0x1F], // 'y'
+    [0x00, 0x00, 0x3F, 0x19, 0x0C, 0x26, 0x3F, 0x00], // 'z'
+    [0x38, 0x0C, 0x0C, 0x07, 0x0C, 0x0C, 0x38, 0x00], // '{'
+    [0x18, 0x18, 0x18, 0x00, 0x18, 0x18, 0x18, 0x00], // '|'
+    [0x07, 0x0C, 0x0C, 0x38, 0x0C, 0x0C, 0x07, 0x00], // '}'
+    [0x6E, 0x3B, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // '~'
+];
+
+/// Why a font description could not be loaded.
+#[derive(Debug)]
+pub enum FontError {
+    Io(io::Error),
+    Parse { line: usize, message: String },
+    /// The glyph cells of a fixed-grid font do not fit its atlas.
+    Grid { cell_width: u32, cell_height: u32 },
+}
+
+impl fmt::Display for FontError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FontError::Io(error) => write!(f, "{}", error),
+            FontError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+            FontError::Grid { cell_width, cell_height } => {
+                write!(f, "{}x{} glyph cells do not fit the atlas", cell_width, cell_height)
+            },
+        }
+    }
+}
+
+impl error::Error for FontError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            FontError::Io(error) => Some(error),
+            FontError::Parse { .. } | FontError::Grid { .. } => None,
+        }
+    }
+}
+
+/// Where a glyph is in the atlas and how it sits on the line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Glyph {
+    /// `None` for glyphs with nothing to draw, like a space.
+    pub source: Option<Rect>,
+    /// Offset of the glyph's top-left corner from the pen position at the top of the line.
+    pub offset: Point,
+    /// How far the pen moves after the glyph.
+    pub advance: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+/// How a string is laid out and drawn.
+#[derive(Debug, Clone, Copy)]
+pub struct TextStyle {
+    pub color: Color,
+    /// Which point of each line the `x` coordinate given when drawing refers to.
+    pub align: Align,
+    /// Width, in pixels, after which lines are wrapped between words.
+    pub wrap_width: Option<u32>,
+    /// Size of a font pixel on screen.
+    pub scale: u32,
+}
+
+impl Default for TextStyle {
+    fn default() -> TextStyle {
+        TextStyle { color: Color::WHITE, align: Align::Left, wrap_width: None, scale: 1 }
+    }
+}
+
+impl TextStyle {
+    pub fn new(color: Color) -> TextStyle {
+        TextStyle { color, ..TextStyle::default() }
+    }
+
+    pub fn align(mut self, align: Align) -> TextStyle {
+        self.align = align;
+        self
+    }
+
+    pub fn wrap(mut self, width: u32) -> TextStyle {
+        self.wrap_width = Some(width);
+        self
+    }
+
+    pub fn scale(mut self, scale: u32) -> TextStyle {
+        self.scale = scale.max(1);
+        self
+    }
+}
+
+/// A glyph placed on screen: copy `source` from the atlas to `dest`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlacedGlyph {
+    pub source: Rect,
+    pub dest: Rect,
+}
+
+/// Where every glyph of a string goes, and the box they fill.
+#[derive(Debug, Clone)]
+pub struct Layout {
+    pub glyphs: Vec<PlacedGlyph>,
+    /// Covers every line, from the top of the first to the bottom of the last.
+    /// Like any SDL rectangle it is at least one pixel wide and high.
+    pub bounds: Rect,
+}
+
+/// Glyph metrics of a font, independent of the atlas image they refer to.
+#[derive(Debug, Clone, Default)]
+pub struct Metrics {
+    pub line_height: u32,
+    pub glyphs: HashMap<char, Glyph>,
+    /// Extra advance between two characters, from BMFont `kerning` lines.
+    pub kerning: HashMap<(char, char), i32>,
+}
+
+impl Metrics {
+    /// Metrics of a fixed-grid atlas: `cell_width` by `cell_height` cells, read left to right
+    /// and top to bottom, holding consecutive characters starting at `first`.
+    pub fn grid(atlas_width: u32, atlas_height: u32, cell_width: u32, cell_height: u32, first: char)
+        -> Result<Metrics, FontError>
+    {
+        if cell_width == 0 || cell_height == 0 || cell_width > atlas_width || cell_height > atlas_height {
+            return Err(FontError::Grid { cell_width, cell_height });
+        }
+
+        let columns = atlas_width / cell_width;
+        let rows = atlas_height / cell_height;
+
+        let mut glyphs = HashMap::new();
+        for cell in 0..columns * rows {
+            let character = match char::from_u32(first as u32 + cell) {
+                None => continue,
+                Some(character) => character
+            };
+            let x = (cell % columns * cell_width) as i32;
+            let y = (cell / columns * cell_height) as i32;
+            glyphs.insert(character, Glyph {
+                source: Some(Rect::new(x, y, cell_width, cell_height)),
+                offset: Point::new(0, 0),
+                advance: cell_width as i32,
+            });
+        }
+
+        Ok(Metrics { line_height: cell_height, glyphs, kerning: HashMap::new() })
+    }
+
+    /// Parses an AngelCode BMFont description in its text format, returning the metrics
+    /// and the file name of its atlas. Only single-page fonts are supported.
+    pub fn parse_bmfont(source: &str) -> Result<(Metrics, String), FontError> {
+        let mut metrics = Metrics::default();
+        let mut page = None;
+
+        for (index, line) in source.lines().enumerate() {
+            let line_number = index + 1;
+            let parse_error = |message: String| FontError::Parse { line: line_number, message };
+
+            let mut tokens = tokenize(line).into_iter();
+            let tag = match tokens.next() {
+                None => continue,
+                Some(tag) => tag
+            };
+
+            let mut attributes = HashMap::new();
+            for token in tokens {
+                match token.split_once('=') {
+                    None => return Err(parse_error(format!("expected key=value, found '{}'", token))),
+                    Some((key, value)) => attributes.insert(key.to_string(), value.to_string())
+                };
+            }
+            let number = |key: &str| -> Result<i32, FontError> {
+                let value = attributes.get(key)
+                    .ok_or_else(|| parse_error(format!("{} is missing {}", tag, key)))?;
+                value.parse()
+                    .map_err(|_| parse_error(format!("invalid {} '{}'", key, value)))
+            };
+            let character = |key: &str| -> Result<char, FontError> {
+                let id = number(key)?;
+                char::from_u32(id as u32).ok_or_else(|| parse_error(format!("invalid character id {}", id)))
+            };
+
+            match tag.as_str() {
+                "common" => {
+                    metrics.line_height = number("lineHeight")?.max(0) as u32;
+                    if number("pages")? != 1 {
+                        return Err(parse_error("only single-page fonts are supported".to_string()));
+                    }
+                },
+                "page" => {
+                    let file = attributes.get("file")
+                        .ok_or_else(|| parse_error("page is missing file".to_string()))?;
+                    page = Some(file.clone());
+                },
+                "char" => {
+                    let (width, height) = (number("width")?, number("height")?);
+                    let source = match width > 0 && height > 0 {
+                        false => None,
+                        true => Some(Rect::new(number("x")?, number("y")?, width as u32, height as u32))
+                    };
+                    let glyph = Glyph {
+                        source,
+                        offset: Point::new(number("xoffset")?, number("yoffset")?),
+                        advance: number("xadvance")?,
+                    };
+                    metrics.glyphs.insert(character("id")?, glyph);
+                },
+                "kerning" => {
+                    metrics.kerning.insert((character("first")?, character("second")?), number("amount")?);
+                },
+                _ => ()
+            }
+        }
+
+        match page {
+            None => Err(FontError::Parse {
+                line: source.lines().count(),
+                message: "the font has no page line".to_string(),
+            }),
+            Some(page) => Ok((metrics, page))
+        }
+    }
+
+    /// Glyph drawn for `character`, falling back to `?` for characters the font lacks.
+    pub fn glyph(&self, character: char) -> Option<&Glyph> {
+        self.glyphs.get(&character).or_else(|| self.glyphs.get(&'?'))
+    }
+
+    /// Width of a single line of text, before scaling.
+    pub fn line_width(&self, line: &str) -> u32 {
+        let mut width = 0;
+        let mut previous = None;
+
+        for character in line.chars() {
+            if let Some(previous) = previous {
+                width += self.kerning.get(&(previous, character)).copied().unwrap_or(0);
+            }
+            width += self.glyph(character).map_or(0, |glyph| glyph.advance);
+            previous = Some(character);
+        }
+
+        width.max(0) as u32
+    }
+
+    /// Splits `text` into the lines it is drawn as, wrapping between words when asked to.
+    ///
+    /// A single word wider than the wrap width gets a line of its own and overflows it.
+    pub fn lines<'t>(&self, text: &'t str, style: &TextStyle) -> Vec<&'t str> {
+        let mut lines = Vec::new();
+
+        for paragraph in text.split('\n') {
+            let wrap_width = match style.wrap_width {
+                None => {
+                    lines.push(paragraph);
+                    continue;
+                },
+                Some(wrap_width) => wrap_width
+            };
+
+            let mut start = 0;
+            let mut end = 0;
+            for (offset, word) in words(paragraph) {
+                let candidate = &paragraph[start..offset + word.len()];
+                if end > start && self.line_width(candidate) * style.scale > wrap_width {
+                    lines.push(&paragraph[start..end]);
+                    start = offset;
+                }
+                end = offset + word.len();
+            }
+            lines.push(&paragraph[start..end]);
+        }
+
+        lines
+    }
+
+    /// Size `text` takes when drawn with `style`.
+    pub fn measure(&self, text: &str, style: &TextStyle) -> (u32, u32) {
+        let scale = style.scale.max(1);
+        let lines = self.lines(text, style);
+        let width = lines.iter().map(|line| self.line_width(line)).max().unwrap_or(0);
+        (width * scale, lines.len() as u32 * self.line_height * scale)
+    }
+
+    /// Places every glyph of `text` with the lines anchored at `x` (per `style.align`) and
+    /// the first line's top at `y`.
+    pub fn layout(&self, text: &str, x: i32, y: i32, style: &TextStyle) -> Layout {
+        let scale = style.scale.max(1);
+        let line_height = (self.line_height * scale) as i32;
+        let lines = self.lines(text, style);
+
+        let mut glyphs = Vec::new();
+        let mut left_edge = i32::MAX;
+        let mut widest = 0;
+
+        for (row, line) in lines.iter().enumerate() {
+            let width = (self.line_width(line) * scale) as i32;
+            let left = match style.align {
+                Align::Left => x,
+                Align::Center => x - width / 2,
+                Align::Right => x - width,
+            };
+            left_edge = left_edge.min(left);
+            widest = widest.max(left + width);
+
+            let top = y + row as i32 * line_height;
+            let mut pen = left;
+            let mut previous = None;
+            for character in line.chars() {
+                if let Some(previous) = previous {
+                    pen += self.kerning.get(&(previous, character)).copied().unwrap_or(0) * scale as i32;
+                }
+                previous = Some(character);
+
+                let glyph = match self.glyph(character) {
+                    None => continue,
+                    Some(glyph) => glyph
+                };
+                if let Some(source) = glyph.source {
+                    glyphs.push(PlacedGlyph {
+                        source,
+                        dest: Rect::new(
+                            pen + glyph.offset.x() * scale as i32,
+                            top + glyph.offset.y() * scale as i32,
+                            source.width() * scale,
+                            source.height() * scale
+                        ),
+                    });
+                }
+                pen += glyph.advance * scale as i32;
+            }
+        }
+
+        let left_edge = if lines.is_empty() { x } else { left_edge };
+        let bounds = Rect::new(
+            left_edge,
+            y,
+            (widest - left_edge).max(0) as u32,
+            (lines.len() as i32 * line_height) as u32
+        );
+
+        Layout { glyphs, bounds }
+    }
+}
+
+/// Splits a line on spaces, keeping each word's byte offset.
+fn words(line: &str) -> impl Iterator<Item = (usize, &str)> {
+    line.split(' ')
+        .scan(0, |offset, word| {
+            let start = *offset;
+            *offset += word.len() + 1;
+            Some((start, word))
+        })
+        .filter(|(_, word)| !word.is_empty())
+}
+
+/// Splits a BMFont line on whitespace, keeping quoted values (`face="Comic Sans"`) whole.
+fn tokenize(line: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut token = String::new();
+    let mut quoted = false;
+
+    for character in line.chars() {
+        match character {
+            '"' => quoted = !quoted,
+            _ if character.is_whitespace() && !quoted => {
+                if !token.is_empty() {
+                    tokens.push(std::mem::take(&mut token));
+                }
+            },
+            _ => token.push(character)
+        }
+    }
+    if !token.is_empty() {
+        tokens.push(token);
+    }
+
+    tokens
+}
+
+/// A bitmap font: white glyphs on a transparent atlas, tinted when drawn.
+pub struct Font {
+    name: String,
+    atlas: Surface<'static>,
+    metrics: Metrics,
+}
+
+impl Font {
+    /// Builds a font from any atlas image. Atlases without an alpha channel are drawn
+    /// with black as the transparent color.
+    pub fn new(name: &str, atlas: &SurfaceRef, metrics: Metrics) -> Result<Font, Error> {
+        let mut converted = atlas.convert_format(PixelFormatEnum::ARGB8888)
+            .map_err(Error::surface_convert(name))?;
+        if !atlas.pixel_format_enum().supports_alpha() {
+            converted.set_color_key(true, Color::BLACK).map_err(Error::surface_convert(name))?;
+        }
+        converted.set_blend_mode(BlendMode::Blend).map_err(Error::surface_convert(name))?;
+
+        Ok(Font { name: name.to_string(), atlas: converted, metrics })
+    }
+
+    /// The built-in 8x8 font, which needs neither font files nor SDL_ttf.
+    pub fn builtin() -> Result<Font, Error> {
+        let columns = 16;
+        let rows = (GLYPHS.len() as u32).div_ceil(columns);
+        let mut atlas = Surface::new(columns * GLYPH_SIZE, rows * GLYPH_SIZE, PixelFormatEnum::RGBA32)
+            .map_err(Error::surface_convert(BUILTIN))?;
+
+        let pitch = atlas.pitch() as usize;
+        atlas.with_lock_mut(|pixels| {
+            for (index, glyph) in GLYPHS.iter().enumerate() {
+                let cell_x = index % columns as usize * GLYPH_SIZE as usize;
+                let cell_y = index / columns as usize * GLYPH_SIZE as usize;
+                for (row, bits) in glyph.iter().enumerate() {
+                    for bit in 0..GLYPH_SIZE as usize {
+                        if bits >> bit & 1 == 1 {
+                            let offset = (cell_y + row) * pitch + (cell_x + bit) * 4;
+                            pixels[offset..offset + 4].copy_from_slice(&[0xFF; 4]);
+                        }
+                    }
+                }
+            }
+        });
+
+        let metrics = Metrics::grid(atlas.width(), atlas.height(), GLYPH_SIZE, GLYPH_SIZE, ' ')
+            .map_err(|source| Error::Font { path: BUILTIN.to_string(), source })?;
+        Font::new(BUILTIN, &atlas, metrics)
+    }
+
+    /// Loads a fixed-grid font: an atlas of `cell_width` by `cell_height` cells holding
+    /// consecutive characters from `first`.
+    pub fn load_grid(assets: &mut Assets, path: &str, cell_width: u32, cell_height: u32, first: char)
+        -> Result<Font, Error>
+    {
+        let atlas = assets.surface(path)?;
+        let metrics = Metrics::grid(atlas.width(), atlas.height(), cell_width, cell_height, first)
+            .map_err(|source| Error::Font { path: path.to_string(), source })?;
+        Font::new(path, &atlas, metrics)
+    }
+
+    /// Loads an AngelCode BMFont `.fnt` (text format) and the atlas next to it.
+    pub fn load_bmfont(assets: &mut Assets, path: &str) -> Result<Font, Error> {
+        let font_error = |source| Error::Font { path: path.to_string(), source };
+
+        let mut description = String::new();
+        assets.open(path)?
+            .read_to_string(&mut description)
+            .map_err(|error| font_error(FontError::Io(error)))?;
+        let (metrics, page) = Metrics::parse_bmfont(&description).map_err(font_error)?;
+
+        // Asset paths always use forward slashes, whatever the platform.
+        let page_path = match Path::new(path).parent().and_then(Path::to_str) {
+            Some(directory) if !directory.is_empty() => format!("{}/{}", directory, page),
+            _ => page
+        };
+        let atlas = assets.surface(&page_path)?;
+        Font::new(&page_path, &atlas, metrics)
+    }
+
+    /// Name of the font in errors: the path of its atlas, or of its description.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    /// Size `text` takes when drawn with `style`.
+    pub fn measure(&self, text: &str, style: &TextStyle) -> (u32, u32) {
+        self.metrics.measure(text, style)
+    }
+
+    /// Uploads the atlas to a texture the first time `textures` is asked for it, so the
+    /// font can be drawn on the canvas `textures` makes textures for.
+    ///
+    /// Fonts with the same name share their texture.
+    pub fn texture<'f, 't>(&'f self, textures: &'f mut TextureCache<'t>) -> Result<FontTexture<'f, 't>, Error> {
+        let atlas = textures.generated(&self.name, || Ok(&self.atlas))?;
+        Ok(FontTexture { font: self, atlas })
+    }
+
+    /// Draws `text` onto a surface and returns the bounds it covers.
+    pub fn draw_to_surface(&mut self, surface: &mut SurfaceRef, text: &str, x: i32, y: i32, style: &TextStyle)
+        -> Result<Rect, Error>
+    {
+        let layout = self.metrics.layout(text, x, y, style);
+
+        self.atlas.set_color_mod(style.color);
+        self.atlas.set_alpha_mod(style.color.a);
+
+        for glyph in &layout.glyphs {
+            self.atlas.blit_scaled(glyph.source, surface, glyph.dest).map_err(Error::sdl(Error::Render))?;
+        }
+
+        Ok(layout.bounds)
+    }
+}
+
+/// A [`Font`] with its atlas uploaded to a texture, from [`Font::texture`].
+pub struct FontTexture<'f, 't> {
+    font: &'f Font,
+    atlas: &'f mut Texture<'t>,
+}
+
+impl<'f, 't> FontTexture<'f, 't> {
+    pub fn font(&self) -> &Font {
+        self.font
+    }
+
+    /// Draws `text` on a canvas and returns the bounds it covers.
+    pub fn draw(&mut self, canvas: &mut WindowCanvas, text: &str, x: i32, y: i32, style: &TextStyle)
+        -> Result<Rect, Error>
+    {
+        let layout = self.font.metrics.layout(text, x, y, style);
+
+        self.atlas.set_color_mod(style.color.r, style.color.g, style.color.b);
+        self.atlas.set_alpha_mod(style.color.a);
+
+        for glyph in &layout.glyphs {
+            canvas.copy(self.atlas, glyph.source, glyph.dest).map_err(Error::sdl(Error::Render))?;
+        }
+
+        Ok(layout.bounds)
+    }
+}
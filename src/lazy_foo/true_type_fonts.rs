@@ -0,0 +1,99 @@
+use crate::Error;
+use crate::lazy_foo::app::Options;
+
+#[cfg(feature = "ttf")]
+mod lesson {
+    use sdl2::event::Event;
+    use sdl2::keyboard::Keycode;
+    use sdl2::pixels::Color;
+    use sdl2::rect::Rect;
+    use sdl2::render::WindowCanvas;
+    use crate::Error;
+    use crate::lazy_foo::app::{App, Options, SCREEN_WIDTH};
+    use crate::lazy_foo::scene::{self, Scene, Textures, Transition};
+    use crate::lazy_foo::ttf::RenderMode;
+
+    const FONT_PATH: &str = "imgs/true_type_fonts/DejaVuSansMono.ttf";
+    const TEXT: &str = "The quick brown fox jumps over the lazy dog";
+
+    const MIN_SIZE: u16 = 8;
+    const MAX_SIZE: u16 = 32;
+
+    const MODES: [RenderMode; 3] = [
+        RenderMode::Solid,
+        RenderMode::Shaded { background: Color::RGB(0xFF, 0xFF, 0xC0) },
+        RenderMode::Blended,
+    ];
+
+    pub struct TrueTypeFonts {
+        size: u16,
+    }
+
+    impl TrueTypeFonts {
+        pub fn boxed() -> Box<dyn Scene> {
+            Box::new(TrueTypeFonts { size: 16 })
+        }
+    }
+
+    impl Scene for TrueTypeFonts {
+        fn on_event(&mut self, _app: &mut App, event: &Event) -> Result<Transition, Error> {
+            match *event {
+                Event::KeyDown { keycode: Some(Keycode::Up), .. } => self.size = (self.size + 2).min(MAX_SIZE),
+                Event::KeyDown { keycode: Some(Keycode::Down), .. } => self.size = (self.size - 2).max(MIN_SIZE),
+                _ => ()
+            }
+
+            Ok(Transition::Stay)
+        }
+
+        fn render(&mut self, canvas: &mut WindowCanvas, textures: &mut Textures) -> Result<(), Error> {
+            canvas.set_draw_color(Color::WHITE);
+            canvas.clear();
+
+            let size = self.size;
+            let description = match textures.glyph_metrics(FONT_PATH, size, 'g')? {
+                None => format!("{} pt, no glyph for 'g'", size),
+                Some(metrics) => format!(
+                    "{} pt, 'g': x {}..{}, y {}..{}, advance {}",
+                    size, metrics.minx, metrics.maxx, metrics.miny, metrics.maxy, metrics.advance
+                )
+            };
+
+            let mut lines = Vec::new();
+            for mode in MODES {
+                lines.push((TEXT, size, mode));
+            }
+            lines.push((description.as_str(), 12, RenderMode::Blended));
+            lines.push(("Up/Down to change the size", 12, RenderMode::Blended));
+
+            let mut y = 60;
+            for (line, line_size, mode) in lines {
+                let texture = textures.text(FONT_PATH, line, line_size, mode, Color::BLACK)?;
+                let query = texture.query();
+                let x = (SCREEN_WIDTH as i32 - query.width as i32) / 2;
+                canvas.copy(texture, None, Rect::new(x, y, query.width, query.height))
+                    .map_err(Error::sdl(Error::Render))?;
+                y += query.height as i32 + 40;
+            }
+
+            Ok(())
+        }
+    }
+
+    pub fn run(options: &Options) -> Result<(), Error> {
+        scene::run(options, vec![TrueTypeFonts::boxed()])
+    }
+}
+
+#[cfg(feature = "ttf")]
+pub use lesson::TrueTypeFonts;
+
+#[cfg(feature = "ttf")]
+pub fn run(options: &Options) -> Result<(), Error> {
+    lesson::run(options)
+}
+
+#[cfg(not(feature = "ttf"))]
+pub fn run(_options: &Options) -> Result<(), Error> {
+    Err(Error::FeatureDisabled("ttf"))
+}
@@ -0,0 +1,144 @@
+//! TrueType text through SDL_ttf, enabled by the `ttf` feature.
+
+use std::collections::{HashMap, HashSet};
+use sdl2::pixels::Color;
+use sdl2::render::Texture;
+use sdl2::ttf::{self, Font, GlyphMetrics, Sdl2TtfContext};
+use crate::Error;
+use crate::error::SdlError;
+use crate::lazy_foo::assets::{Assets, TextureCache};
+
+/// How SDL_ttf turns a string into pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RenderMode {
+    /// Fastest, one color and no anti-aliasing.
+    Solid,
+    /// Anti-aliased against an opaque background.
+    Shaded { background: Color },
+    /// Anti-aliased with a transparent background; the slowest.
+    Blended,
+}
+
+/// SDL_ttf, initialized for as long as this lives. Lessons get theirs from `App::ttf`.
+pub struct TrueType {
+    context: Sdl2TtfContext,
+}
+
+impl TrueType {
+    pub fn init() -> Result<TrueType, Error> {
+        let context = ttf::init().map_err(|error| Error::TtfInit(SdlError(error.to_string())))?;
+        Ok(TrueType { context })
+    }
+}
+
+/// One font file, opened at each point size it is asked for.
+pub struct FontFamily<'ttf> {
+    ttf: &'ttf Sdl2TtfContext,
+    path: String,
+    sizes: HashMap<u16, Font<'ttf, 'static>>,
+}
+
+impl<'ttf> FontFamily<'ttf> {
+    pub fn new(ttf: &'ttf TrueType, path: &str) -> FontFamily<'ttf> {
+        FontFamily { ttf: &ttf.context, path: path.to_string(), sizes: HashMap::new() }
+    }
+
+    /// Returns the font at `size` points, opening it on first use.
+    pub fn font(&mut self, assets: &Assets, size: u16) -> Result<&Font<'ttf, 'static>, Error> {
+        if !self.sizes.contains_key(&size) {
+            let rwops = assets.open(&self.path)?;
+            let font = self.ttf.load_font_from_rwops(rwops, size)
+                .map_err(|message| Error::FontLoad { path: self.path.clone(), source: SdlError(message) })?;
+            self.sizes.insert(size, font);
+        }
+
+        Ok(&self.sizes[&size])
+    }
+
+    /// Metrics of `character` at `size` points, if the font has a glyph for it.
+    pub fn glyph_metrics(&mut self, assets: &Assets, size: u16, character: char)
+        -> Result<Option<GlyphMetrics>, Error>
+    {
+        Ok(self.font(assets, size)?.find_glyph_metrics(character))
+    }
+}
+
+/// Textures of rendered strings, kept for every size, mode and color they were drawn in.
+///
+/// The textures themselves live in a [`TextureCache`], so the cache does not borrow the
+/// renderer.
+pub struct TextCache<'ttf> {
+    family: FontFamily<'ttf>,
+    /// Keys of the strings rendered so far in the texture cache.
+    keys: HashSet<String>,
+}
+
+impl<'ttf> TextCache<'ttf> {
+    pub fn new(family: FontFamily<'ttf>) -> TextCache<'ttf> {
+        TextCache { family, keys: HashSet::new() }
+    }
+
+    pub fn family(&mut self) -> &mut FontFamily<'ttf> {
+        &mut self.family
+    }
+
+    /// Returns `text` rendered at `size` points, rendering it on first use.
+    ///
+    /// SDL_ttf cannot render an empty string, so `text` must not be empty.
+    pub fn text<'c, 't>(
+        &mut self,
+        assets: &Assets,
+        textures: &'c mut TextureCache<'t>,
+        text: &str,
+        size: u16,
+        mode: RenderMode,
+        color: Color,
+    ) -> Result<&'c mut Texture<'t>, Error> {
+        let key = format!("{} {}pt {:?} {:?}: {}", self.family.path, size, mode, color, text);
+        let family = &mut self.family;
+        let texture = textures.generated(&key, || {
+            let rendering = family.font(assets, size)?.render(text);
+            let surface = match mode {
+                RenderMode::Solid => rendering.solid(color),
+                RenderMode::Shaded { background } => rendering.shaded(color, background),
+                RenderMode::Blended => rendering.blended(color),
+            };
+            surface.map_err(|error| Error::TextRender { text: text.to_string(), source: SdlError(error.to_string()) })
+        });
+        let texture = texture.map_err(|error| match error {
+            Error::TextureCreate { source, .. } => Error::TextRender { text: text.to_string(), source: SdlError(source.to_string()) },
+            error => error
+        })?;
+
+        self.keys.insert(key);
+        Ok(texture)
+    }
+
+    /// Drops every rendered string, e.g. when the text shown changes all the time.
+    pub fn clear(&mut self, textures: &mut TextureCache) {
+        for key in self.keys.drain() {
+            textures.forget(&key);
+        }
+    }
+}
+
+/// A [`TextCache`] for every font file drawn with so far, for as long as SDL_ttf lives.
+///
+/// Scenes draw text through `Textures::text`, which borrows the fonts of the scene runner,
+/// so they only need to name the font file.
+pub struct Fonts<'ttf> {
+    ttf: &'ttf TrueType,
+    caches: HashMap<String, TextCache<'ttf>>,
+}
+
+impl<'ttf> Fonts<'ttf> {
+    pub fn new(ttf: &'ttf TrueType) -> Fonts<'ttf> {
+        Fonts { ttf, caches: HashMap::new() }
+    }
+
+    /// Returns the text cache of the font at `path`, creating it on first use.
+    pub fn cache(&mut self, path: &str) -> &mut TextCache<'ttf> {
+        let ttf = self.ttf;
+        self.caches.entry(path.to_string()).or_insert_with(|| TextCache::new(FontFamily::new(ttf, path)))
+    }
+}
@@ -0,0 +1,147 @@
+use sdl2::{sys, GameControllerSubsystem, JoystickSubsystem};
+use sdl2::controller::{Axis, Button};
+use crate::Error;
+use crate::error::SdlError;
+
+/// Every button, in SDL's order, which is also the order of the virtual joystick's buttons.
+const BUTTONS: [Button; 21] = [
+    Button::A, Button::B, Button::X, Button::Y,
+    Button::Back, Button::Guide, Button::Start,
+    Button::LeftStick, Button::RightStick, Button::LeftShoulder, Button::RightShoulder,
+    Button::DPadUp, Button::DPadDown, Button::DPadLeft, Button::DPadRight,
+    Button::Misc1, Button::Paddle1, Button::Paddle2, Button::Paddle3, Button::Paddle4,
+    Button::Touchpad,
+];
+
+/// Every axis, in SDL's order, which is also the order of the virtual joystick's axes.
+const AXES: [Axis; 6] = [
+    Axis::LeftX, Axis::LeftY, Axis::RightX, Axis::RightY, Axis::TriggerLeft, Axis::TriggerRight,
+];
+
+pub const NAME: &str = "sdl2_learn Virtual Controller";
+
+fn sdl_error() -> SdlError {
+    SdlError(sdl2::get_error())
+}
+
+/// A game controller that only exists in software, driven by the program instead of a hand.
+///
+/// SDL treats it like any other controller: it is announced with `ControllerDeviceAdded`,
+/// opened with `GameControllerSubsystem::open`, and what it is set to comes back as
+/// `ControllerButtonDown`/`ControllerAxisMotion` events the next time events are pumped.
+/// It is unplugged when dropped.
+pub struct VirtualController {
+    joystick: *mut sys::SDL_Joystick,
+    instance_id: u32,
+    _game_controller: GameControllerSubsystem,
+}
+
+impl VirtualController {
+    /// Plugs in a new virtual controller with every standard button and axis.
+    pub fn attach(joystick: &JoystickSubsystem, game_controller: &GameControllerSubsystem)
+        -> Result<VirtualController, Error>
+    {
+        VirtualController::attach_as(joystick, game_controller, sys::SDL_JoystickType::SDL_JOYSTICK_TYPE_GAMECONTROLLER)
+    }
+
+    /// Like [`VirtualController::attach`], but announced as a `kind` joystick.
+    ///
+    /// SDL builds a virtual joystick's GUID from its type, so controllers of different
+    /// kinds tell apart like two different models would.
+    pub fn attach_as(joystick: &JoystickSubsystem, game_controller: &GameControllerSubsystem, kind: sys::SDL_JoystickType)
+        -> Result<VirtualController, Error>
+    {
+        let index = unsafe {
+            sys::SDL_JoystickAttachVirtual(
+                kind,
+                AXES.len() as i32,
+                BUTTONS.len() as i32,
+                0,
+            )
+        };
+        if index < 0 {
+            return Err(Error::VirtualController(sdl_error()));
+        }
+
+        let raw = unsafe { sys::SDL_JoystickOpen(index) };
+        if raw.is_null() {
+            let error = sdl_error();
+            unsafe { sys::SDL_JoystickDetachVirtual(index) };
+            return Err(Error::VirtualController(error));
+        }
+        let instance_id = unsafe { sys::SDL_JoystickInstanceID(raw) } as u32;
+        let controller = VirtualController { joystick: raw, instance_id, _game_controller: game_controller.clone() };
+
+        // Give the joystick a mapping where button and axis numbers are SDL's own.
+        let guid = joystick.device_guid(index as u32)
+            .map_err(|error| Error::VirtualController(SdlError(error.to_string())))?;
+        let mut mapping = format!("{},{},", guid.string(), NAME);
+        for (number, button) in BUTTONS.iter().enumerate() {
+            mapping.push_str(&format!("{}:b{},", button.string(), number));
+        }
+        for (number, axis) in AXES.iter().enumerate() {
+            mapping.push_str(&format!("{}:a{},", axis.string(), number));
+        }
+        game_controller.add_mapping(&mapping)
+            .map_err(|error| Error::VirtualController(SdlError(error.to_string())))?;
+
+        // Axes start in the middle of their range, which is a half pulled trigger.
+        controller.set_axis(Axis::TriggerLeft, 0)?;
+        controller.set_axis(Axis::TriggerRight, 0)?;
+
+        Ok(controller)
+    }
+
+    /// The joystick instance id events from this controller carry in `which`.
+    pub fn instance_id(&self) -> u32 {
+        self.instance_id
+    }
+
+    pub fn set_button(&self, button: Button, pressed: bool) -> Result<(), Error> {
+        let result = unsafe { sys::SDL_JoystickSetVirtualButton(self.joystick, button as i32, pressed as u8) };
+        if result < 0 {
+            return Err(Error::VirtualController(sdl_error()));
+        }
+        Ok(())
+    }
+
+    pub fn press(&self, button: Button) -> Result<(), Error> {
+        self.set_button(button, true)
+    }
+
+    pub fn release(&self, button: Button) -> Result<(), Error> {
+        self.set_button(button, false)
+    }
+
+    /// Moves `axis` to `value`, as the controller would report it: sticks from -32768 to
+    /// 32767 and triggers from 0 to 32767.
+    pub fn set_axis(&self, axis: Axis, value: i16) -> Result<(), Error> {
+        // Triggers are mapped from the joystick's whole range to the controller's half range.
+        let raw = match axis {
+            Axis::TriggerLeft | Axis::TriggerRight => (value.max(0) as i32 * 2 - 32768) as i16,
+            _ => value
+        };
+
+        let result = unsafe { sys::SDL_JoystickSetVirtualAxis(self.joystick, axis as i32, raw) };
+        if result < 0 {
+            return Err(Error::VirtualController(sdl_error()));
+        }
+        Ok(())
+    }
+}
+
+impl Drop for VirtualController {
+    fn drop(&mut self) {
+        unsafe {
+            sys::SDL_JoystickClose(self.joystick);
+
+            // The device index moves as other joysticks come and go; look it up again.
+            for index in 0..sys::SDL_NumJoysticks() {
+                if sys::SDL_JoystickGetDeviceInstanceID(index) as u32 == self.instance_id {
+                    sys::SDL_JoystickDetachVirtual(index);
+                    break;
+                }
+            }
+        }
+    }
+}
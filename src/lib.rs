@@ -1,4 +1,31 @@
+pub mod error;
+pub use error::Error;
+
 pub mod lazy_foo {
+    pub mod analog;
+    pub mod app;
+    pub mod assets;
+    #[cfg(feature = "mixer")]
+    pub mod audio;
+    pub mod capture;
+    pub mod controllers;
+    pub mod draw;
+    mod embedded;
+    pub mod input;
+    pub mod main_loop;
+    pub mod mapping;
+    pub mod menu;
+    pub mod scene;
+    pub mod script;
+    pub mod synth;
+    pub mod text;
+    pub mod virtual_controller;
+    #[cfg(feature = "ttf")]
+    pub mod ttf;
+
+    mod lesson;
+    pub use lesson::{find, Lesson, LessonFn, SurfaceFn};
+
     mod create_window;
     pub use create_window::run as create_window;
 
@@ -9,7 +36,7 @@ pub mod lazy_foo {
     pub use event_driven::run as event_driven;
 
     mod key_presses;
-    pub use key_presses::run as key_presses;
+    pub use key_presses::{run as key_presses, KeyPresses};
 
     mod optimized_surface;
     pub use optimized_surface::run as optimized_surface;
@@ -18,11 +45,79 @@ pub mod lazy_foo {
     pub use loading_other_image::run as loading_other_image;
 
     mod texture_loading;
-    pub use texture_loading::run as texture_loading;
+    pub use texture_loading::{run as texture_loading, TextureLoading};
 
     mod geometry_rendering;
-    pub use geometry_rendering::run as geometry_rendering;
+    pub use geometry_rendering::{run as geometry_rendering, GeometryRendering};
 
     mod gamepads_and_joysticks;
-    pub use gamepads_and_joysticks::run as gamepads_and_joysticks;
+    pub use gamepads_and_joysticks::{run as gamepads_and_joysticks, GamepadsAndJoysticks};
+
+    mod true_type_fonts;
+    pub use true_type_fonts::run as true_type_fonts;
+    #[cfg(feature = "ttf")]
+    pub use true_type_fonts::TrueTypeFonts;
+
+    mod sound_effects_and_music;
+    pub use sound_effects_and_music::run as sound_effects_and_music;
+    #[cfg(feature = "mixer")]
+    pub use sound_effects_and_music::SoundEffectsAndMusic;
+
+    mod synthesizer;
+    pub use synthesizer::{run as synthesizer, Synthesizer};
+
+    mod rebinding;
+    pub use rebinding::{run as rebinding, Rebinding};
+
+    mod gamepad_visualizer;
+    pub use gamepad_visualizer::{run as gamepad_visualizer, GamepadVisualizer};
+
+    mod mapping_wizard;
+    pub use mapping_wizard::{run as mapping_wizard, MappingWizard};
+
+    mod analog_sticks;
+    pub use analog_sticks::{run as analog_sticks, AnalogSticks};
+
+    /// Every lesson the launcher knows about, in tutorial order.
+    pub const LESSONS: &[Lesson] = &[
+        Lesson::new("create_window", "Open a window and fill it with white", create_window)
+            .surface(create_window::run_in),
+        Lesson::new("image_screen", "Blit a BMP onto the window surface", image_screen)
+            .surface(image_screen::run_in),
+        Lesson::new("event_driven", "Keep the window open until it is closed", event_driven)
+            .surface(event_driven::run_in),
+        Lesson::new("key_presses", "Swap images with the arrow keys", key_presses)
+            .scene(KeyPresses::boxed),
+        Lesson::new("optimized_surface", "Convert and stretch a surface", optimized_surface)
+            .surface(optimized_surface::run_in),
+        Lesson::new("loading_other_image", "Load a PNG through SDL_image", loading_other_image)
+            .surface(loading_other_image::run_in),
+        Lesson::new("texture_loading", "Render a PNG texture with a renderer", texture_loading)
+            .scene(TextureLoading::boxed),
+        Lesson::new("geometry_rendering", "Draw rectangles, lines and points", geometry_rendering)
+            .scene(GeometryRendering::boxed),
+        Lesson::new("gamepads_and_joysticks", "Swap images with controller buttons", gamepads_and_joysticks)
+            .scene(GamepadsAndJoysticks::boxed),
+        #[cfg(feature = "ttf")]
+        Lesson::new("true_type_fonts", "Render text with SDL_ttf (ttf feature)", true_type_fonts)
+            .scene(TrueTypeFonts::boxed),
+        // Listed either way, so the lesson numbers do not depend on the features built.
+        #[cfg(not(feature = "ttf"))]
+        Lesson::new("true_type_fonts", "Render text with SDL_ttf (ttf feature)", true_type_fonts),
+        #[cfg(feature = "mixer")]
+        Lesson::new("sound_effects_and_music", "Play sounds and music with SDL_mixer (mixer feature)", sound_effects_and_music)
+            .scene(SoundEffectsAndMusic::boxed),
+        #[cfg(not(feature = "mixer"))]
+        Lesson::new("sound_effects_and_music", "Play sounds and music with SDL_mixer (mixer feature)", sound_effects_and_music),
+        Lesson::new("synthesizer", "Play notes through an audio callback", synthesizer)
+            .scene(Synthesizer::boxed),
+        Lesson::new("rebinding", "Bind keys and buttons to actions", rebinding)
+            .scene(Rebinding::boxed),
+        Lesson::new("gamepad_visualizer", "Draw every button, stick and trigger of a controller", gamepad_visualizer)
+            .scene(GamepadVisualizer::boxed),
+        Lesson::new("mapping_wizard", "Write a mapping for an unknown controller", mapping_wizard)
+            .scene(MappingWizard::boxed),
+        Lesson::new("analog_sticks", "Move a sprite with dead zones and response curves", analog_sticks)
+            .scene(AnalogSticks::boxed),
+    ];
 }
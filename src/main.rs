@@ -1,5 +1,220 @@
+use std::env;
+use std::error::Error;
+use std::path::Path;
+use std::process::ExitCode;
 use sdl2_learn::lazy_foo;
+use sdl2_learn::lazy_foo::app::Options;
+use sdl2_learn::lazy_foo::capture::Capture;
+use sdl2_learn::lazy_foo::scene;
+use sdl2_learn::lazy_foo::script::Script;
 
-fn main() {
-    lazy_foo::geometry_rendering();
+const USAGE: &str = "\
+Usage: sdl2_learn list
+       sdl2_learn run <lesson name | index> [options]
+       sdl2_learn chain <lesson>... [options]    (Escape moves on to the next lesson)
+       sdl2_learn menu [options]                 (pick lessons in a window)
+
+Options:
+    --headless                Run without a display, using SDL's dummy video driver
+    --frames <N>              Exit after N frames
+    --capture <path>          Save a frame as PNG (or BMP, by extension)
+    --capture-frame <N>       Frame to save with --capture (default: 1)
+    --script <path>           Feed the lesson the events listed in an event script
+    --record <path>           Record every event the lesson receives to a replay script
+    --assets <dir>            Look for images and mappings in <dir> first
+                              (then $SDL2_LEARN_ASSETS, the current and the executable's directory)
+    --audio-out <path>        Write the lesson's audio to <path> as raw samples instead of playing it
+    --bindings <path>         Load key and button bindings from <path> and save rebound ones there
+                              (default: $XDG_CONFIG_HOME/sdl2_learn/bindings.toml)
+    --virtual-controllers <n> Plug in <n> virtual controllers, driven by the --script's controller events
+    --mappings <path>         Load game controller mappings from <path> too (may be given more than once)
+    --user-mappings <path>    Load game controller mappings from <path> and add new ones there
+                              (default: $XDG_CONFIG_HOME/sdl2_learn/mappings.txt)";
+
+fn list() {
+    for (index, lesson) in lazy_foo::LESSONS.iter().enumerate() {
+        println!("{:>2}  {:<24} {}", index, lesson.name, lesson.description);
+    }
+}
+
+/// Joins an error and its sources into a single line.
+fn describe(error: &dyn Error) -> String {
+    let mut message = error.to_string();
+
+    let mut source = error.source();
+    while let Some(cause) = source {
+        message.push_str(&format!(" ({})", cause));
+        source = cause.source();
+    }
+
+    message
+}
+
+fn report(error: &dyn Error) {
+    eprintln!("{} failed: {}", env!("CARGO_PKG_NAME"), describe(error));
+}
+
+fn parse_frame(flag: &str, value: Option<&&str>) -> Result<u32, String> {
+    let value = value.ok_or(format!("{} expects a frame number", flag))?;
+    value.parse().map_err(|_| format!("Invalid frame number '{}' for {}!", value, flag))
+}
+
+fn parse_options(args: &[&str]) -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut capture_frame = None;
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match *arg {
+            "--headless" => options.headless = true,
+            "--frames" => options.frame_budget = Some(parse_frame(arg, args.next())?),
+            "--capture" => {
+                let path = args.next().ok_or("--capture expects a file path")?;
+                options.capture = Some(Capture { frame: 0, path: path.into() });
+            },
+            "--capture-frame" => capture_frame = Some(parse_frame(arg, args.next())?),
+            "--script" => {
+                let path = args.next().ok_or("--script expects a file path")?;
+                let script = Script::load(Path::new(path))
+                    .map_err(|error| describe(&error))?;
+                options.script = Some(script);
+            },
+            "--record" => {
+                let path = args.next().ok_or("--record expects a file path")?;
+                options.record = Some(path.into());
+            },
+            "--assets" => {
+                let path = args.next().ok_or("--assets expects a directory")?;
+                options.asset_root = Some(path.into());
+            },
+            "--audio-out" => {
+                let path = args.next().ok_or("--audio-out expects a file path")?;
+                options.audio_output = Some(path.into());
+            },
+            "--virtual-controllers" => {
+                let count = args.next().ok_or("--virtual-controllers expects a number")?;
+                options.virtual_controllers = count.parse()
+                    .map_err(|_| format!("Invalid number of controllers '{}' for --virtual-controllers!", count))?;
+            },
+            "--mappings" => {
+                let path = args.next().ok_or("--mappings expects a file path")?;
+                options.mappings.push(path.into());
+            },
+            "--user-mappings" => {
+                let path = args.next().ok_or("--user-mappings expects a file path")?;
+                options.user_mappings = Some(path.into());
+            },
+            "--bindings" => {
+                let path = args.next().ok_or("--bindings expects a file path")?;
+                options.bindings = Some(path.into());
+            },
+            _ => return Err(format!("Unknown option '{}'!", arg))
+        }
+    }
+
+    match &mut options.capture {
+        Some(capture) => capture.frame = capture_frame.unwrap_or(1),
+        None if capture_frame.is_some() => return Err("--capture-frame needs --capture!".to_string()),
+        None => ()
+    }
+
+    Ok(options)
+}
+
+fn run(selector: &str, options: &Options) -> ExitCode {
+    match lazy_foo::find(lazy_foo::LESSONS, selector) {
+        None => {
+            eprintln!("Unknown lesson '{}'! Run `sdl2_learn list` to see the available lessons.", selector);
+            ExitCode::FAILURE
+        },
+        Some(lesson) => match (lesson.run)(options) {
+            Err(error) => {
+                report(&error);
+                ExitCode::FAILURE
+            },
+            Ok(()) => ExitCode::SUCCESS
+        }
+    }
+}
+
+/// Runs several scene lessons one after the other in the same window.
+fn chain(selectors: &[&str], options: &Options) -> ExitCode {
+    if selectors.is_empty() {
+        eprintln!("{}", USAGE);
+        return ExitCode::FAILURE;
+    }
+
+    let mut scenes = Vec::new();
+    for selector in selectors {
+        match lazy_foo::find(lazy_foo::LESSONS, selector) {
+            None => {
+                eprintln!("Unknown lesson '{}'! Run `sdl2_learn list` to see the available lessons.", selector);
+                return ExitCode::FAILURE;
+            },
+            Some(lesson) => match (lesson.scene, lesson.surface) {
+                (Some(scene), _) => scenes.push(scene()),
+                (None, Some(_)) => {
+                    eprintln!("Lesson '{}' draws on a window surface of its own and can not be chained!", lesson.name);
+                    return ExitCode::FAILURE;
+                },
+                (None, None) => {
+                    eprintln!("Lesson '{}' was built without the feature it needs!", lesson.name);
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
+    }
+
+    match scene::run(options, scenes) {
+        Err(error) => {
+            report(&error);
+            ExitCode::FAILURE
+        },
+        Ok(()) => ExitCode::SUCCESS
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    match args.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+        ["list"] => {
+            list();
+            ExitCode::SUCCESS
+        },
+        ["run", selector, flags @ ..] => match parse_options(flags) {
+            Err(message) => {
+                eprintln!("{}\n\n{}", message, USAGE);
+                ExitCode::FAILURE
+            },
+            Ok(options) => run(selector, &options)
+        },
+        ["menu", flags @ ..] => match parse_options(flags) {
+            Err(message) => {
+                eprintln!("{}\n\n{}", message, USAGE);
+                ExitCode::FAILURE
+            },
+            Ok(options) => match lazy_foo::menu::run(&options) {
+                Err(error) => {
+                    report(&error);
+                    ExitCode::FAILURE
+                },
+                Ok(()) => ExitCode::SUCCESS
+            }
+        },
+        ["chain", rest @ ..] => {
+            let lessons = rest.iter().take_while(|arg| !arg.starts_with("--")).count();
+            match parse_options(&rest[lessons..]) {
+                Err(message) => {
+                    eprintln!("{}\n\n{}", message, USAGE);
+                    ExitCode::FAILURE
+                },
+                Ok(options) => chain(&rest[..lessons], &options)
+            }
+        },
+        _ => {
+            eprintln!("{}", USAGE);
+            ExitCode::FAILURE
+        }
+    }
 }
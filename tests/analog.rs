@@ -0,0 +1,169 @@
+mod common;
+
+use sdl2_learn::lazy_foo::analog::{normalize, AxisConfig, AxisProcessor, ResponseCurve, StickConfig, TriggerConfig};
+use sdl2_learn::lazy_foo::app::Options;
+use sdl2_learn::lazy_foo::script::Script;
+
+const SPRITE: Option<[u8; 4]> = Some([0x40, 0xC0, 0xFF, 0xFF]);
+
+fn close(actual: f32, expected: f32) -> bool {
+    (actual - expected).abs() < 1e-3
+}
+
+fn stick(radial: f32, axial: f32, outer: f32, curve: ResponseCurve) -> StickConfig {
+    StickConfig { radial_dead_zone: radial, axial_dead_zone: axial, outer_dead_zone: outer, curve }
+}
+
+/// Raw axis value for a fraction of the stick's travel.
+fn raw(fraction: f32) -> i16 {
+    (fraction * 32767.0).round() as i16
+}
+
+#[test]
+fn raw_values_are_normalized_to_unit_range() {
+    assert_eq!(normalize(0), 0.0);
+    assert_eq!(normalize(32767), 1.0);
+    assert_eq!(normalize(-32768), -1.0);
+    assert!(close(normalize(16384), 0.5));
+}
+
+#[test]
+fn curves_keep_their_ends() {
+    let curves = [
+        ResponseCurve::Linear,
+        ResponseCurve::Exponential(2.0),
+        ResponseCurve::Custom(vec![(0.5, 0.1)]),
+    ];
+    for curve in &curves {
+        assert!(close(curve.apply(0.0), 0.0), "{:?}", curve);
+        assert!(close(curve.apply(1.0), 1.0), "{:?}", curve);
+        assert!(close(curve.apply(2.0), 1.0), "{:?}", curve);
+    }
+}
+
+#[test]
+fn exponential_curve_is_finer_near_the_center() {
+    let curve = ResponseCurve::Exponential(2.0);
+
+    assert!(close(curve.apply(0.5), 0.25));
+    assert!(curve.apply(0.3) < ResponseCurve::Linear.apply(0.3));
+}
+
+#[test]
+fn custom_curve_interpolates_between_points() {
+    let curve = ResponseCurve::Custom(vec![(0.5, 0.1), (0.8, 0.4)]);
+
+    assert!(close(curve.apply(0.25), 0.05));
+    assert!(close(curve.apply(0.5), 0.1));
+    assert!(close(curve.apply(0.65), 0.25));
+    assert!(close(curve.apply(0.9), 0.7));
+}
+
+#[test]
+fn radial_dead_zone_centers_small_movements_in_any_direction() {
+    let config = stick(0.2, 0.0, 1.0, ResponseCurve::Linear);
+
+    assert_eq!(config.process(raw(0.15), 0), (0.0, 0.0));
+    assert_eq!(config.process(raw(0.1), raw(0.1)), (0.0, 0.0));
+    assert_ne!(config.process(raw(0.15), raw(0.15)), (0.0, 0.0));
+}
+
+#[test]
+fn radial_dead_zone_rescales_the_rest_of_the_travel() {
+    let config = stick(0.2, 0.0, 1.0, ResponseCurve::Linear);
+
+    let (x, y) = config.process(raw(0.6), 0);
+    assert!(close(x, 0.5));
+    assert_eq!(y, 0.0);
+
+    // Direction is kept: a diagonal stays a diagonal.
+    let (x, y) = config.process(raw(0.6), raw(0.6));
+    assert!(close(x, y));
+}
+
+#[test]
+fn axial_dead_zone_keeps_straight_pushes_straight() {
+    let config = stick(0.0, 0.2, 1.0, ResponseCurve::Linear);
+
+    let (x, y) = config.process(raw(0.1), raw(-0.9));
+    assert_eq!(x, 0.0);
+    assert!(close(y, -0.875));
+}
+
+#[test]
+fn outer_dead_zone_reaches_full_output_early() {
+    let config = stick(0.0, 0.0, 0.8, ResponseCurve::Linear);
+
+    assert!(close(config.process(raw(0.8), 0).0, 1.0));
+    assert!(close(config.process(raw(0.4), 0).0, 0.5));
+    assert!(close(config.process(-32768, 0).0, -1.0));
+}
+
+#[test]
+fn stick_output_never_leaves_the_unit_circle() {
+    let config = stick(0.1, 0.05, 0.9, ResponseCurve::Exponential(1.5));
+
+    let (x, y) = config.process(32767, 32767);
+    assert!(close((x * x + y * y).sqrt(), 1.0));
+}
+
+#[test]
+fn curve_is_applied_after_the_dead_zones() {
+    let config = stick(0.2, 0.0, 1.0, ResponseCurve::Exponential(2.0));
+
+    assert!(close(config.process(raw(0.6), 0).0, 0.25));
+}
+
+#[test]
+fn trigger_amount_ignores_its_dead_zones() {
+    let config = TriggerConfig { dead_zone: 0.1, outer_dead_zone: 0.9, ..TriggerConfig::default() };
+
+    assert_eq!(config.process(0), 0.0);
+    assert_eq!(config.process(raw(0.05)), 0.0);
+    assert!(close(config.process(raw(0.5)), 0.5));
+    assert_eq!(config.process(raw(0.95)), 1.0);
+    assert_eq!(config.process(-32768), 0.0);
+}
+
+#[test]
+fn trigger_press_has_hysteresis() {
+    let config = TriggerConfig {
+        dead_zone: 0.0,
+        outer_dead_zone: 1.0,
+        curve: ResponseCurve::Linear,
+        press_threshold: 0.5,
+        release_threshold: 0.3,
+    };
+
+    assert!(!config.pressed(raw(0.4), false));
+    assert!(config.pressed(raw(0.5), false));
+    assert!(config.pressed(raw(0.4), true));
+    assert!(!config.pressed(raw(0.2), true));
+}
+
+#[test]
+fn controllers_get_their_own_settings() {
+    let mut processor = AxisProcessor::default();
+    processor.config_mut("pad").left_stick.curve = ResponseCurve::Exponential(3.0);
+
+    assert_eq!(processor.config("pad").left_stick.curve, ResponseCurve::Exponential(3.0));
+    assert_eq!(processor.config("other"), &AxisConfig::default());
+
+    processor.configure("other", AxisConfig { right_stick: stick(0.3, 0.0, 1.0, ResponseCurve::Linear), ..AxisConfig::default() });
+    assert_eq!(processor.config("other").right_stick.radial_dead_zone, 0.3);
+}
+
+#[test]
+fn pushed_stick_moves_the_sprite() {
+    let centered = common::capture_lesson("analog_sticks", 10, Options { virtual_controllers: 1, ..Options::headless(10) });
+    assert_eq!(centered.pixel(320, 240), SPRITE);
+
+    let options = Options {
+        script: Some(Script::parse("1 controlleraxismotion 0 leftx 32767").unwrap()),
+        virtual_controllers: 1,
+        ..Options::headless(10)
+    };
+    let pushed = common::capture_lesson("analog_sticks", 10, options);
+    assert_ne!(pushed.pixel(320, 240), SPRITE);
+    assert_eq!(pushed.pixel(360, 240), SPRITE);
+}
@@ -0,0 +1,52 @@
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::{Duration, SystemTime};
+use sdl2_learn::Error;
+use sdl2_learn::lazy_foo::assets::Assets;
+use sdl2_learn::lazy_foo::capture::Image;
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write_image(path: &Path, color: [u8; 4], modified: SystemTime) {
+    Image::new(2, 2, color.repeat(4)).save(path).unwrap();
+    File::options().write(true).open(path).unwrap().set_modified(modified).unwrap();
+}
+
+#[test]
+fn missing_asset_lists_searched_directories() {
+    let roots = vec![scratch_dir("empty_a"), scratch_dir("empty_b")];
+    let assets = Assets::with_roots(roots.clone());
+
+    match assets.resolve("imgs/nowhere.bmp") {
+        Err(Error::AssetNotFound { searched, .. }) => assert_eq!(searched, roots),
+        other => panic!("expected AssetNotFound, got {:?}", other),
+    }
+}
+
+#[test]
+fn changed_files_are_reloaded_and_broken_ones_ignored() {
+    let root = scratch_dir("hot_reload");
+    let path = root.join("square.bmp");
+    let start = SystemTime::now() - Duration::from_secs(60);
+    write_image(&path, [0xFF, 0x00, 0x00, 0xFF], start);
+
+    let mut assets = Assets::with_roots(vec![root]);
+    let first = assets.surface("square.bmp").unwrap();
+    assert!(assets.reload_changed().is_empty());
+
+    write_image(&path, [0x00, 0x00, 0xFF, 0xFF], start + Duration::from_secs(1));
+    assert_eq!(assets.reload_changed(), vec!["square.bmp".to_string()]);
+    assert_eq!(assets.generation("square.bmp"), Some(1));
+    assert!(!Rc::ptr_eq(&first, &assets.surface("square.bmp").unwrap()));
+
+    fs::write(&path, b"not a bitmap").unwrap();
+    File::options().write(true).open(&path).unwrap()
+        .set_modified(start + Duration::from_secs(2)).unwrap();
+    assert!(assets.reload_changed().is_empty());
+    assert_eq!(assets.generation("square.bmp"), Some(1));
+}
@@ -0,0 +1,68 @@
+#![cfg(feature = "mixer")]
+
+mod common;
+
+use std::fs;
+use std::path::Path;
+use sdl2_learn::lazy_foo::{self, audio};
+use sdl2_learn::lazy_foo::app::Options;
+use sdl2_learn::lazy_foo::script::Script;
+
+/// Samples (both channels) in the four mixer buffers the tests want to look at.
+const MIN_SAMPLES: usize = 4 * audio::CHUNK_SIZE as usize * 2;
+
+/// Runs the sound lesson headless with `script` and returns the raw samples it played.
+///
+/// The disk driver writes in real time while headless frames do not wait, so the lesson
+/// runs again for twice as long until it has written at least [`MIN_SAMPLES`].
+fn samples_played(script: &str) -> Vec<i16> {
+    let lesson = lazy_foo::find(lazy_foo::LESSONS, "sound_effects_and_music").unwrap();
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join("sound_effects_and_music.raw");
+    let _lock = common::sdl_lock();
+
+    let mut frames = 30;
+    loop {
+        let options = Options {
+            script: Some(Script::parse(script).unwrap()),
+            audio_output: Some(path.clone()),
+            ..Options::headless(frames)
+        };
+        (lesson.run)(&options).unwrap();
+
+        let samples: Vec<i16> = fs::read(&path).unwrap()
+            .chunks_exact(2)
+            .map(|bytes| i16::from_le_bytes([bytes[0], bytes[1]]))
+            .collect();
+        if samples.len() >= MIN_SAMPLES {
+            return samples;
+        }
+        assert!(frames < 30 << 10, "only {} samples written after {} frames", samples.len(), frames);
+        frames *= 2;
+    }
+}
+
+#[test]
+fn silence_when_nothing_is_pressed() {
+    let samples = samples_played("");
+
+    assert!(!samples.is_empty());
+    assert!(samples.iter().all(|&sample| sample == 0));
+}
+
+#[test]
+fn key_press_plays_a_sound() {
+    let samples = samples_played("1 keydown Up");
+
+    assert!(samples.iter().any(|&sample| sample != 0));
+}
+
+#[test]
+fn panned_sound_only_plays_on_one_side() {
+    let samples = samples_played("1 keydown Left");
+    let (left, right): (Vec<i16>, Vec<i16>) = samples.chunks_exact(2)
+        .map(|frame| (frame[0], frame[1]))
+        .unzip();
+
+    assert!(left.iter().any(|&sample| sample != 0));
+    assert!(right.iter().all(|&sample| sample == 0));
+}
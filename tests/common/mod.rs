@@ -0,0 +1,33 @@
+// Each test binary only uses some of these helpers.
+#![allow(dead_code)]
+
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+use sdl2_learn::lazy_foo;
+use sdl2_learn::lazy_foo::app::Options;
+use sdl2_learn::lazy_foo::capture::{Capture, Image};
+
+/// Only one `Sdl` context can be alive at a time, so tests touching SDL take turns.
+pub fn sdl_lock() -> MutexGuard<'static, ()> {
+    static LOCK: Mutex<()> = Mutex::new(());
+
+    LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Runs lesson `name` headless with `options` and returns what it presented on `frame`.
+pub fn capture_lesson(name: &str, frame: u32, options: Options) -> Image {
+    let lesson = lazy_foo::find(lazy_foo::LESSONS, name).unwrap();
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join(format!("{}-{}.png", name, frame));
+    let options = Options {
+        capture: Some(Capture { frame, path: path.clone() }),
+        ..options
+    };
+
+    let _lock = sdl_lock();
+    (lesson.run)(&options).unwrap();
+    Image::load(&path).unwrap()
+}
+
+pub fn asset(path: &str) -> Image {
+    Image::load(&Path::new(env!("CARGO_MANIFEST_DIR")).join(path)).unwrap()
+}
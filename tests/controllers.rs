@@ -0,0 +1,212 @@
+mod common;
+
+use sdl2::controller::{Axis, Button};
+use sdl2_learn::lazy_foo::app::{App, AppBuilder, Options};
+use sdl2_learn::lazy_foo::capture::Image;
+use sdl2::sys::SDL_JoystickType;
+use sdl2_learn::lazy_foo::controllers::{Controllers, MAX_PLAYERS};
+use sdl2_learn::lazy_foo::script::Script;
+use sdl2_learn::lazy_foo::virtual_controller::VirtualController;
+
+/// Rows the lesson's player list is drawn over.
+const LABELS_HEIGHT: u32 = 40;
+
+fn headless_app() -> App {
+    AppBuilder::new("SDL Tutorial").options(&Options::headless(100)).build().unwrap()
+}
+
+/// Feeds every pending event to `controllers`.
+fn pump(app: &mut App, controllers: &mut Controllers) {
+    app.next_frame().unwrap();
+    for event in app.poll_events().unwrap() {
+        controllers.handle_event(app, &event).unwrap();
+    }
+}
+
+/// Whether `capture` shows `expected` everywhere below the player list.
+fn shows_below_labels(capture: &Image, expected: &Image) -> bool {
+    let diff = capture.compare(expected, 0).diff;
+
+    (LABELS_HEIGHT..diff.height())
+        .all(|y| (0..diff.width()).all(|x| diff.pixel(x, y) != Some([0xFF, 0x00, 0x00, 0xFF])))
+}
+
+#[test]
+fn virtual_button_shows_its_image() {
+    let options = Options {
+        script: Some(Script::parse("1 controllerbuttondown 0 b").unwrap()),
+        virtual_controllers: 1,
+        ..Options::headless(3)
+    };
+    let capture = common::capture_lesson("gamepads_and_joysticks", 3, options);
+
+    assert!(shows_below_labels(&capture, &common::asset("imgs/gamepads_and_joysticks/B.bmp")));
+    assert!(!shows_below_labels(&capture, &common::asset("imgs/gamepads_and_joysticks/A.bmp")));
+}
+
+#[test]
+fn every_virtual_controller_gets_a_player() {
+    let _lock = common::sdl_lock();
+    let mut app = headless_app();
+    app.attach_virtual_controller().unwrap();
+    app.attach_virtual_controller().unwrap();
+
+    let mut controllers = Controllers::new();
+    controllers.open_all(&mut app).unwrap();
+    pump(&mut app, &mut controllers);
+
+    assert_eq!(controllers.len(), 2);
+    let second = app.virtual_controllers()[1].instance_id();
+    assert_eq!(controllers.player_for(second).unwrap().slot(), 1);
+
+    app.virtual_controllers()[1].press(Button::X).unwrap();
+    pump(&mut app, &mut controllers);
+
+    let player = controllers.player(1).unwrap();
+    assert!(player.pressed(Button::X));
+    assert_eq!(player.last_button(), Some(Button::X));
+    assert_eq!(controllers.player(0).unwrap().last_button(), None);
+}
+
+#[test]
+fn new_virtual_controller_has_released_triggers() {
+    let _lock = common::sdl_lock();
+    let mut app = headless_app();
+    app.attach_virtual_controller().unwrap();
+
+    let mut controllers = Controllers::new();
+    controllers.open_all(&mut app).unwrap();
+    pump(&mut app, &mut controllers);
+
+    let player = controllers.player(0).unwrap();
+    assert_eq!(player.axis(Axis::TriggerLeft), 0);
+    assert_eq!(player.axis(Axis::TriggerRight), 0);
+    assert_eq!(player.axis(Axis::LeftX), 0);
+}
+
+#[test]
+fn unplugged_players_keep_their_slot() {
+    let _lock = common::sdl_lock();
+    let mut app = headless_app();
+    app.attach_virtual_controller().unwrap();
+    app.attach_virtual_controller().unwrap();
+
+    let mut controllers = Controllers::new();
+    controllers.open_all(&mut app).unwrap();
+    pump(&mut app, &mut controllers);
+
+    app.detach_virtual_controller(0);
+    pump(&mut app, &mut controllers);
+    assert!(controllers.player(0).is_none());
+    assert!(controllers.player(1).is_some());
+
+    app.attach_virtual_controller().unwrap();
+    pump(&mut app, &mut controllers);
+    let replugged = app.virtual_controllers()[1].instance_id();
+    assert_eq!(controllers.player_for(replugged).unwrap().slot(), 0);
+}
+
+#[test]
+fn controllers_fill_the_lowest_free_slots() {
+    let _lock = common::sdl_lock();
+    let mut app = headless_app();
+    for _ in 0..3 {
+        app.attach_virtual_controller().unwrap();
+    }
+
+    let mut controllers = Controllers::new();
+    controllers.open_all(&mut app).unwrap();
+    pump(&mut app, &mut controllers);
+
+    for (slot, controller) in app.virtual_controllers().iter().enumerate() {
+        assert_eq!(controllers.player_for(controller.instance_id()).unwrap().slot(), slot);
+    }
+    assert!(controllers.player(3).is_none());
+}
+
+#[test]
+fn replugged_controller_finds_its_slot_by_guid() {
+    let _lock = common::sdl_lock();
+    let mut app = headless_app();
+    let joystick = app.joystick().unwrap().clone();
+    let game_controller = app.game_controller().unwrap().clone();
+    let arcade = || VirtualController::attach_as(&joystick, &game_controller, SDL_JoystickType::SDL_JOYSTICK_TYPE_ARCADE_STICK);
+    app.attach_virtual_controller().unwrap();
+    let stick = arcade().unwrap();
+
+    let mut controllers = Controllers::new();
+    controllers.open_all(&mut app).unwrap();
+    pump(&mut app, &mut controllers);
+    let pad_guid = controllers.player(0).unwrap().guid().to_string();
+    assert_ne!(controllers.player(1).unwrap().guid(), pad_guid);
+
+    // Both slots are free; the stick skips the pad's slot for its own.
+    drop(stick);
+    app.detach_virtual_controller(0);
+    pump(&mut app, &mut controllers);
+    assert!(controllers.is_empty());
+
+    let stick = arcade().unwrap();
+    pump(&mut app, &mut controllers);
+    assert_eq!(controllers.player_for(stick.instance_id()).unwrap().slot(), 1);
+
+    app.attach_virtual_controller().unwrap();
+    pump(&mut app, &mut controllers);
+    assert_eq!(controllers.player(0).unwrap().guid(), pad_guid);
+}
+
+#[test]
+fn controllers_of_the_same_model_keep_their_own_slots() {
+    let _lock = common::sdl_lock();
+    let mut app = headless_app();
+    app.attach_virtual_controller().unwrap();
+    app.attach_virtual_controller().unwrap();
+
+    let mut controllers = Controllers::new();
+    controllers.open_all(&mut app).unwrap();
+    pump(&mut app, &mut controllers);
+    let (first, second) = (controllers.player(0).unwrap(), controllers.player(1).unwrap());
+    assert_eq!(first.guid(), second.guid());
+    // Virtual joysticks have no serial number, so they are told apart by GUID.
+    assert_eq!((first.serial(), second.serial()), (None, None));
+
+    let kept = app.virtual_controllers()[0].instance_id();
+    app.detach_virtual_controller(1);
+    pump(&mut app, &mut controllers);
+    app.attach_virtual_controller().unwrap();
+    pump(&mut app, &mut controllers);
+
+    let replugged = app.virtual_controllers()[1].instance_id();
+    assert_eq!(controllers.player_for(kept).unwrap().slot(), 0);
+    assert_eq!(controllers.player_for(replugged).unwrap().slot(), 1);
+}
+
+#[test]
+fn extra_controllers_wait_for_a_free_slot() {
+    let _lock = common::sdl_lock();
+    let mut app = headless_app();
+    for _ in 0..=MAX_PLAYERS {
+        app.attach_virtual_controller().unwrap();
+    }
+
+    let mut controllers = Controllers::new();
+    controllers.open_all(&mut app).unwrap();
+    pump(&mut app, &mut controllers);
+
+    assert_eq!(controllers.len(), MAX_PLAYERS);
+    let waiting = app.virtual_controllers()[MAX_PLAYERS].instance_id();
+    assert!(controllers.player_for(waiting).is_none());
+
+    app.detach_virtual_controller(1);
+    pump(&mut app, &mut controllers);
+    assert_eq!(controllers.len(), MAX_PLAYERS);
+    assert_eq!(controllers.player_for(waiting).unwrap().slot(), 1);
+}
+
+#[test]
+fn lesson_asks_for_a_controller_without_one() {
+    let capture = common::capture_lesson("gamepads_and_joysticks", 3, Options::headless(3));
+
+    assert!(shows_below_labels(&capture, &common::asset("imgs/gamepads_and_joysticks/press.bmp")));
+    assert!(!capture.compare(&common::asset("imgs/gamepads_and_joysticks/press.bmp"), 0).matches());
+}
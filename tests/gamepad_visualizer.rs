@@ -0,0 +1,36 @@
+mod common;
+
+use sdl2_learn::lazy_foo::app::Options;
+use sdl2_learn::lazy_foo::capture::Image;
+use sdl2_learn::lazy_foo::script::Script;
+
+const HELD: Option<[u8; 4]> = Some([0xFF, 0xFF, 0x00, 0xFF]);
+
+fn visualize(script: &str) -> Image {
+    let options = Options {
+        script: Some(Script::parse(script).unwrap()),
+        virtual_controllers: 1,
+        ..Options::headless(3)
+    };
+    common::capture_lesson("gamepad_visualizer", 3, options)
+}
+
+#[test]
+fn held_button_is_lit() {
+    let capture = visualize("1 controllerbuttondown 0 a");
+
+    // Inside the A and B circles, below their labels.
+    assert_eq!(capture.pixel(490, 268), HELD);
+    assert_ne!(capture.pixel(518, 240), HELD);
+}
+
+#[test]
+fn stick_and_trigger_follow_their_axes() {
+    let capture = visualize("1 controlleraxismotion 0 leftx 32767\n1 controlleraxismotion 0 triggerleft 32767");
+
+    // The left stick's dot is at the right edge of its circle, the left trigger bar is full.
+    assert_eq!(capture.pixel(268, 320), HELD);
+    assert_ne!(capture.pixel(230, 320), HELD);
+    assert_eq!(capture.pixel(136, 64), HELD);
+    assert_ne!(capture.pixel(504, 64), HELD);
+}
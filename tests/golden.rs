@@ -0,0 +1,68 @@
+mod common;
+
+use std::env;
+use std::path::Path;
+use sdl2_learn::lazy_foo::app::Options;
+use sdl2_learn::lazy_foo::capture::Image;
+
+/// Per-channel difference tolerated between a capture and its golden image.
+const TOLERANCE: u8 = 8;
+
+/// Compares `actual` against the image at `golden`, saving a diff image next to the
+/// capture on failure.
+fn assert_matches(name: &str, actual: &Image, golden: &Path) {
+    let expected = Image::load(golden).unwrap();
+    let comparison = actual.compare(&expected, TOLERANCE);
+    if comparison.matches() {
+        return;
+    }
+
+    let diff_path = Path::new(env!("CARGO_TARGET_TMPDIR")).join(format!("{}.diff.png", name));
+    comparison.diff.save(&diff_path).unwrap();
+    panic!(
+        "{} differs from {}: {} pixels off by up to {} (tolerance {}), diff saved to {}",
+        name,
+        golden.display(),
+        comparison.mismatched,
+        comparison.max_delta,
+        TOLERANCE,
+        diff_path.display()
+    );
+}
+
+/// Compares `actual` against `tests/golden/<name>.png`.
+///
+/// Set `SDL2_LEARN_BLESS=1` to overwrite the golden image with the capture instead.
+fn assert_matches_golden(name: &str, actual: &Image) {
+    let golden = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(format!("{}.png", name));
+
+    if env::var_os("SDL2_LEARN_BLESS").is_some() {
+        actual.save(&golden).unwrap();
+        return;
+    }
+
+    assert_matches(name, actual, &golden);
+}
+
+#[test]
+fn geometry_rendering_draws_every_shape() {
+    let capture = common::capture_lesson("geometry_rendering", 2, Options::headless(2));
+
+    assert_eq!(capture.pixel(200, 150), Some([0xFF, 0x00, 0x00, 0xFF]), "filled quad");
+    assert_eq!(capture.pixel(106, 200), Some([0x00, 0xFF, 0x00, 0xFF]), "outline");
+    assert_eq!(capture.pixel(50, 240), Some([0x00, 0x00, 0xFF, 0xFF]), "horizontal line");
+    assert_eq!(capture.pixel(320, 8), Some([0xFF, 0xFF, 0x00, 0xFF]), "dotted line");
+    assert_eq!(capture.pixel(320, 10), Some([0xFF, 0xFF, 0xFF, 0xFF]), "gap in dotted line");
+
+    assert_matches_golden("geometry_rendering", &capture);
+}
+
+#[test]
+fn texture_loading_stretches_texture_to_window() {
+    let capture = common::capture_lesson("texture_loading", 2, Options::headless(2));
+
+    assert_eq!((capture.width(), capture.height()), (640, 480));
+    assert_matches_golden("texture_loading", &capture);
+}
@@ -0,0 +1,39 @@
+mod common;
+
+use sdl2_learn::Error;
+use sdl2_learn::lazy_foo::{self, Lesson};
+use sdl2_learn::lazy_foo::app::Options;
+
+/// Whether this test was built without cargo feature `feature`.
+fn built_without(feature: &str) -> bool {
+    match feature {
+        "ttf" => !cfg!(feature = "ttf"),
+        "mixer" => !cfg!(feature = "mixer"),
+        _ => false
+    }
+}
+
+fn run_headless(lesson: &Lesson) -> Result<(), Error> {
+    let _lock = common::sdl_lock();
+    // A virtual pad stands in for the controller a headless machine does not have.
+    (lesson.run)(&Options { virtual_controllers: 1, ..Options::headless(3) })
+}
+
+#[test]
+fn every_lesson_runs_headless() {
+    for lesson in lazy_foo::LESSONS {
+        match run_headless(lesson) {
+            Ok(()) => (),
+            // Lessons behind a cargo feature this test was built without.
+            Err(Error::FeatureDisabled(feature)) if built_without(feature) => (),
+            Err(error) => panic!("{} failed headless: {}", lesson.name, error),
+        }
+    }
+}
+
+#[test]
+fn unknown_lesson_is_not_found() {
+    assert!(lazy_foo::find(lazy_foo::LESSONS, "no_such_lesson").is_none());
+    assert_eq!(lazy_foo::find(lazy_foo::LESSONS, "0").unwrap().name, "create_window");
+    assert_eq!(lazy_foo::find(lazy_foo::LESSONS, "key_presses").unwrap().name, "key_presses");
+}
@@ -0,0 +1,216 @@
+mod common;
+
+use sdl2::controller::{Axis, Button};
+use sdl2::event::Event;
+use sdl2::keyboard::{Keycode, Mod, Scancode};
+use sdl2_learn::lazy_foo::input::{self, ActionMap, AxisDirection, Binding, BindingsError, AXIS_THRESHOLD};
+
+fn key(down: bool, keycode: Keycode) -> Event {
+    let scancode = Scancode::from_keycode(keycode);
+    if down {
+        Event::KeyDown { timestamp: 0, window_id: 0, keycode: Some(keycode), scancode, keymod: Mod::NOMOD, repeat: false }
+    } else {
+        Event::KeyUp { timestamp: 0, window_id: 0, keycode: Some(keycode), scancode, keymod: Mod::NOMOD, repeat: false }
+    }
+}
+
+fn button(down: bool, which: u32, button: Button) -> Event {
+    if down {
+        Event::ControllerButtonDown { timestamp: 0, which, button }
+    } else {
+        Event::ControllerButtonUp { timestamp: 0, which, button }
+    }
+}
+
+fn axis(which: u32, axis: Axis, value: i16) -> Event {
+    Event::ControllerAxisMotion { timestamp: 0, which, axis, value }
+}
+
+#[test]
+fn standard_map_has_the_usual_actions() {
+    let map = ActionMap::standard();
+
+    for action in [input::UP, input::DOWN, input::LEFT, input::RIGHT, input::CONFIRM, input::BACK] {
+        assert!(!map.bindings(action).is_empty(), "{} has no bindings", action);
+    }
+    assert!(map.bindings(input::UP).contains(&Binding::Key(Keycode::Up)));
+    assert!(map.bindings(input::UP).contains(&Binding::Axis(Axis::LeftY, AxisDirection::Negative)));
+}
+
+#[test]
+fn press_and_release_are_reported_for_one_frame() {
+    let mut map = ActionMap::standard();
+
+    map.begin_frame();
+    map.handle_event(&key(true, Keycode::Up));
+    assert!(map.pressed(input::UP));
+    assert!(map.just_pressed(input::UP));
+    assert!(!map.just_released(input::UP));
+
+    map.begin_frame();
+    assert!(map.pressed(input::UP));
+    assert!(!map.just_pressed(input::UP));
+
+    map.handle_event(&key(false, Keycode::Up));
+    assert!(!map.pressed(input::UP));
+    assert!(map.just_released(input::UP));
+
+    map.begin_frame();
+    assert!(!map.just_released(input::UP));
+}
+
+#[test]
+fn action_stays_pressed_while_any_binding_is_held() {
+    let mut map = ActionMap::standard();
+
+    map.handle_event(&key(true, Keycode::Return));
+    map.handle_event(&button(true, 0, Button::A));
+    map.handle_event(&key(false, Keycode::Return));
+    assert!(map.pressed(input::CONFIRM));
+
+    map.handle_event(&button(false, 0, Button::A));
+    assert!(!map.pressed(input::CONFIRM));
+}
+
+#[test]
+fn sticks_press_past_the_threshold() {
+    let mut map = ActionMap::standard();
+
+    map.handle_event(&axis(0, Axis::LeftX, AXIS_THRESHOLD - 1));
+    assert!(!map.pressed(input::RIGHT));
+    assert_eq!(map.axis_value(input::RIGHT), 0.0);
+
+    map.handle_event(&axis(0, Axis::LeftX, i16::MAX));
+    assert!(map.pressed(input::RIGHT));
+    assert!(!map.pressed(input::LEFT));
+    assert_eq!(map.axis_value(input::RIGHT), 1.0);
+    assert_eq!(map.axis(input::LEFT, input::RIGHT), 1.0);
+
+    map.handle_event(&axis(0, Axis::LeftX, -16384));
+    assert!(map.pressed(input::LEFT));
+    assert!((map.axis(input::LEFT, input::RIGHT) + 0.5).abs() < 0.01);
+}
+
+#[test]
+fn removed_controllers_release_their_buttons() {
+    let mut map = ActionMap::standard();
+
+    map.handle_event(&button(true, 3, Button::B));
+    assert!(map.pressed(input::BACK));
+
+    map.handle_event(&Event::ControllerDeviceRemoved { timestamp: 0, which: 3 });
+    assert!(!map.pressed(input::BACK));
+}
+
+#[test]
+fn triggered_by_names_the_action_an_event_presses() {
+    let map = ActionMap::standard();
+
+    assert_eq!(map.triggered_by(&key(true, Keycode::Left)), Some(input::LEFT));
+    assert_eq!(map.triggered_by(&button(true, 0, Button::DPadDown)), Some(input::DOWN));
+    assert_eq!(map.triggered_by(&axis(0, Axis::LeftY, -20000)), Some(input::UP));
+    assert_eq!(map.triggered_by(&axis(0, Axis::LeftY, -100)), None);
+    assert_eq!(map.triggered_by(&key(true, Keycode::Q)), None);
+    assert_eq!(map.triggered_by(&key(false, Keycode::Left)), None);
+}
+
+#[test]
+fn bindings_files_replace_standard_actions() {
+    let text = "# WASD\nup = [\"scancode:W\", \"button:y\"]\njump = [\"key:Space\"]\n";
+    let mut map = ActionMap::standard();
+    map.extend(ActionMap::parse(text).unwrap());
+
+    assert_eq!(map.bindings(input::UP), &[Binding::Scancode(Scancode::W), Binding::Button(Button::Y)]);
+    assert_eq!(map.bindings("jump"), &[Binding::Key(Keycode::Space)]);
+    assert!(!map.bindings(input::DOWN).is_empty());
+
+    map.handle_event(&key(true, Keycode::W));
+    assert!(map.pressed(input::UP));
+}
+
+#[test]
+fn bindings_print_as_they_are_parsed() {
+    for text in ["key:Up", "key:Left Shift", "scancode:W", "button:dpup", "axis:lefty-", "axis:triggerright+"] {
+        assert_eq!(Binding::parse(text).unwrap().to_string(), text);
+    }
+}
+
+#[test]
+fn parse_errors_report_their_line() {
+    let cases = [
+        "up = [\"key:Up\"]\ndown [\"key:Down\"]",
+        "up = [\"key:Up\"]\ndown = [\"key:NoSuchKey\"]",
+        "up = [\"key:Up\"]\ndown = [\"axis:leftx\"]",
+        "up = [\"key:Up\"]\ndown = [\"key:Down\"",
+    ];
+
+    for text in cases {
+        match ActionMap::parse(text) {
+            Err(BindingsError::Parse { line, .. }) => assert_eq!(line, 2, "{}", text),
+            Err(error) => panic!("unexpected error: {}", error),
+            Ok(_) => panic!("{} parsed", text),
+        }
+    }
+}
+
+#[test]
+fn config_round_trips() {
+    let map = ActionMap::standard()
+        .bind("quote", Binding::Key(Keycode::Quotedbl))
+        .bind("quote", Binding::Key(Keycode::Backslash));
+    let parsed = ActionMap::parse(&map.to_config()).unwrap();
+
+    assert_eq!(parsed.actions(), map.actions());
+}
+
+#[test]
+fn hash_keys_round_trip() {
+    let map = ActionMap::new()
+        .bind("hash", Binding::Key(Keycode::Hash))
+        .bind("hash", Binding::Key(Keycode::KpHash));
+    let config = map.to_config();
+    assert!(config.contains("\"key:#\""), "{}", config);
+
+    let parsed = ActionMap::parse(&config).unwrap();
+    assert_eq!(parsed.actions(), map.actions());
+}
+
+#[test]
+fn comments_start_outside_quotes() {
+    let map = ActionMap::parse("hash = [\"key:#\"] # up = [\"key:Up\"]").unwrap();
+
+    assert_eq!(map.bindings("hash"), [Binding::Key(Keycode::Hash)]);
+    assert!(map.bindings("up").is_empty());
+}
+
+#[test]
+fn unbind_and_reset() {
+    let mut map = ActionMap::standard();
+    assert_eq!(map.bound_to(Binding::Key(Keycode::Space)), vec![input::CONFIRM]);
+
+    map.unbind(Binding::Key(Keycode::Space));
+    assert!(map.bound_to(Binding::Key(Keycode::Space)).is_empty());
+    map.set(input::UP, Vec::new());
+
+    map.reset(input::CONFIRM);
+    assert_eq!(map.bound_to(Binding::Key(Keycode::Space)), vec![input::CONFIRM]);
+    assert!(map.bindings(input::UP).is_empty());
+
+    map.reset_all();
+    assert_eq!(map.actions(), ActionMap::standard().actions());
+}
+
+#[test]
+fn user_bindings_live_in_the_config_directory() {
+    // The environment is shared by every test, like the SDL context the lock guards.
+    let _lock = common::sdl_lock();
+    let previous = std::env::var_os("XDG_CONFIG_HOME");
+    std::env::set_var("XDG_CONFIG_HOME", "/tmp/config");
+
+    let path = input::user_bindings_path().unwrap();
+    match previous {
+        Some(dir) => std::env::set_var("XDG_CONFIG_HOME", dir),
+        None => std::env::remove_var("XDG_CONFIG_HOME")
+    }
+    assert_eq!(path, std::path::Path::new("/tmp/config/sdl2_learn/bindings.toml"));
+}
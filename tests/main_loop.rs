@@ -0,0 +1,85 @@
+mod common;
+
+use std::thread;
+use std::time::Duration;
+use sdl2_learn::lazy_foo::app::{AppBuilder, Options};
+use sdl2_learn::lazy_foo::main_loop::{FrameStats, LoopConfig, MainLoop};
+
+fn ms(milliseconds: u64) -> Duration {
+    Duration::from_millis(milliseconds)
+}
+
+#[test]
+fn simulated_loop_runs_one_tick_a_frame() {
+    let mut main_loop = MainLoop::new(LoopConfig::default(), true);
+
+    for _ in 0..10 {
+        main_loop.begin_frame();
+        assert_eq!(main_loop.ticks(), 1);
+        assert_eq!(main_loop.alpha(), 0.0);
+        assert_eq!(main_loop.time_left(), None);
+    }
+    assert_eq!(main_loop.tick(), Duration::from_secs(1) / 60);
+}
+
+#[test]
+fn first_real_frame_runs_no_tick() {
+    let mut main_loop = MainLoop::new(LoopConfig::default(), false);
+    main_loop.begin_frame();
+
+    assert_eq!(main_loop.ticks(), 0);
+    assert!(main_loop.time_left().unwrap() <= Duration::from_secs(1) / 60);
+}
+
+#[test]
+fn stalled_frame_runs_at_most_the_tick_limit() {
+    let config = LoopConfig { tick_rate: 1000, frame_cap: None, max_ticks_per_frame: 5 };
+    let mut main_loop = MainLoop::new(config, false);
+
+    main_loop.begin_frame();
+    thread::sleep(ms(20));
+    main_loop.begin_frame();
+
+    // The rest of the stall is dropped rather than carried into the next frames.
+    assert_eq!(main_loop.ticks(), 5);
+    assert_eq!(main_loop.alpha(), 0.0);
+    assert_eq!(main_loop.time_left(), None);
+}
+
+#[test]
+fn stats_cover_recent_frames() {
+    let mut stats = FrameStats::default();
+    assert_eq!(stats.fps(), 0.0);
+
+    for frame_time in [10, 20, 30] {
+        stats.record(ms(frame_time));
+    }
+    assert_eq!(stats.frames(), 3);
+    assert_eq!(stats.last(), ms(30));
+    assert_eq!(stats.average(), ms(20));
+    assert_eq!(stats.min(), ms(10));
+    assert_eq!(stats.max(), ms(30));
+    assert!((stats.fps() - 50.0).abs() < 1e-9);
+
+    // Only the last 120 frames count towards the statistics.
+    for _ in 0..120 {
+        stats.record(ms(40));
+    }
+    assert_eq!(stats.frames(), 123);
+    assert_eq!(stats.min(), ms(40));
+    assert_eq!(stats.average(), ms(40));
+}
+
+#[test]
+fn headless_app_advances_one_tick_a_frame() {
+    let _lock = common::sdl_lock();
+    let mut app = AppBuilder::new("SDL Tutorial").options(&Options::headless(3)).build().unwrap();
+
+    let mut ticks = 0;
+    while app.next_frame().unwrap() {
+        assert_eq!(app.alpha(), 0.0);
+        ticks += app.ticks();
+    }
+    assert_eq!(ticks, 3);
+    assert_eq!(app.stats().frames(), 2);
+}
@@ -0,0 +1,119 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use sdl2::controller::{Axis, Button};
+use sdl2_learn::lazy_foo::input::AxisDirection;
+use sdl2_learn::lazy_foo::mapping::{Guid, Mapping, MappingDb, Platform, Source, Target, CONFIG_VAR};
+
+const PAD: &str = "03000000790000000600000010010000,Test Pad,a:b2,dpup:h0.1,+leftx:a0,lefttrigger:-a2~,platform:Linux,";
+
+fn guid(text: &str) -> Guid {
+    Guid::parse(text).unwrap()
+}
+
+#[test]
+fn parses_every_kind_of_binding() {
+    let mapping = Mapping::parse(PAD).unwrap();
+
+    assert_eq!(mapping.guid, guid("03000000790000000600000010010000"));
+    assert_eq!(mapping.name, "Test Pad");
+    assert_eq!(mapping.platform, Some(Platform::Linux));
+    assert_eq!(mapping.source(Target::Button(Button::A)), Some(Source::Button(2)));
+    assert_eq!(mapping.source(Target::Button(Button::DPadUp)), Some(Source::Hat { index: 0, mask: 1 }));
+    assert_eq!(
+        mapping.source(Target::HalfAxis(Axis::LeftX, AxisDirection::Positive)),
+        Some(Source::Axis { index: 0, half: None, inverted: false }),
+    );
+    assert_eq!(
+        mapping.source(Target::Axis(Axis::TriggerLeft)),
+        Some(Source::Axis { index: 2, half: Some(AxisDirection::Negative), inverted: true }),
+    );
+}
+
+#[test]
+fn mappings_are_written_back_as_they_were_read() {
+    assert_eq!(Mapping::parse(PAD).unwrap().to_string(), PAD);
+}
+
+#[test]
+fn guids_must_be_32_hex_digits() {
+    assert!(Guid::parse("0300000079000000060000001001000").is_err());
+    assert!(Guid::parse("0300000079000000060000001001000g").is_err());
+    assert_eq!(guid("ABCDEF00000000000000000000000000").to_string(), "abcdef00000000000000000000000000");
+}
+
+#[test]
+fn bad_bindings_are_rejected() {
+    let line = |bindings: &str| format!("03000000790000000600000010010000,Pad,{}", bindings);
+
+    assert!(Mapping::parse(&line("jump:b0,")).is_err());
+    assert!(Mapping::parse(&line("a:x0,")).is_err());
+    assert!(Mapping::parse(&line("a:b,")).is_err());
+    assert!(Mapping::parse(&line("dpup:h0,")).is_err());
+    assert!(Mapping::parse(&line("dpup:h0.16,")).is_err());
+    assert!(Mapping::parse(&line("+a:b0,")).is_err());
+    assert!(Mapping::parse(&line("a:b0,a:b1,")).is_err());
+    assert!(Mapping::parse(&line("a:b0,platform:Amiga,")).is_err());
+    assert!(Mapping::parse("03000000790000000600000010010000,,a:b0,").is_err());
+}
+
+#[test]
+fn malformed_lines_are_reported_with_their_line_number() {
+    let text = format!("# comment\n\n{}\nnot a mapping\n03000000790000000600000010010000,Pad,a:q1,\n", PAD);
+    let db = MappingDb::parse("pads.txt", &text);
+
+    assert_eq!(db.mappings().len(), 1);
+    let lines: Vec<usize> = db.errors().iter().map(|error| error.line).collect();
+    assert_eq!(lines, vec![4, 5]);
+    assert!(db.errors()[0].to_string().starts_with("pads.txt, line 4: "));
+}
+
+#[test]
+fn later_mappings_replace_earlier_ones_for_the_same_platform() {
+    let mut db = MappingDb::parse("first", PAD);
+    db.merge(MappingDb::parse("second", &PAD.replace("Test Pad", "Renamed Pad")));
+    db.merge(MappingDb::parse("third", &PAD.replace("Linux", "Windows")));
+
+    assert_eq!(db.mappings().len(), 2);
+    assert_eq!(db.get(guid("03000000790000000600000010010000"), Platform::Linux).unwrap().name, "Renamed Pad");
+}
+
+#[test]
+fn platform_mappings_win_over_untagged_ones() {
+    let untagged = PAD.replace("platform:Linux,", "").replace("Test Pad", "Any Pad");
+    let db = MappingDb::parse("pads", &format!("{}\n{}\n", untagged, PAD));
+    let pad = guid("03000000790000000600000010010000");
+
+    assert_eq!(db.get(pad, Platform::Linux).unwrap().name, "Test Pad");
+    assert_eq!(db.get(pad, Platform::Windows).unwrap().name, "Any Pad");
+
+    let linux: Vec<&str> = db.for_platform(Platform::Linux).iter().map(|mapping| mapping.name.as_str()).collect();
+    assert_eq!(linux, vec!["Test Pad"]);
+}
+
+#[test]
+fn mappings_for_other_platforms_are_left_out() {
+    let db = MappingDb::parse("pads", PAD);
+
+    assert!(db.for_platform(Platform::Windows).is_empty());
+    assert_eq!(db.for_platform(Platform::Linux).len(), 1);
+}
+
+#[test]
+fn environment_mappings_are_read() {
+    env::set_var(CONFIG_VAR, format!("{}\nbroken", PAD));
+    let db = MappingDb::from_env();
+    env::remove_var(CONFIG_VAR);
+
+    assert_eq!(db.mappings().len(), 1);
+    assert_eq!(db.errors()[0].line, 2);
+}
+
+#[test]
+fn shipped_mappings_are_valid() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("controller_mapping.txt");
+    let db = MappingDb::parse("controller_mapping.txt", &fs::read_to_string(path).unwrap());
+
+    assert!(db.errors().is_empty(), "{:?}", db.errors());
+    assert!(!db.for_platform(Platform::Windows).is_empty());
+}
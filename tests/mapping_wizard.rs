@@ -0,0 +1,67 @@
+mod common;
+
+use std::fs;
+use std::path::PathBuf;
+use sdl2::controller::Button;
+use sdl2_learn::lazy_foo;
+use sdl2_learn::lazy_foo::app::{AppBuilder, Options};
+use sdl2_learn::lazy_foo::mapping::{Guid, Mapping, MappingDb, Platform, Source, Target};
+use sdl2_learn::lazy_foo::script::Script;
+use sdl2_learn::lazy_foo::virtual_controller;
+
+fn mappings_path(name: &str) -> PathBuf {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("mapping_wizard").join(format!("{}.txt", name));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+#[test]
+fn pressed_buttons_are_saved_as_a_mapping() {
+    let path = mappings_path("pressed");
+    let script = "\
+        1 controllerbuttondown 0 a\n\
+        2 controllerbuttonup 0 a\n\
+        3 controllerbuttondown 0 x\n\
+        4 controllerbuttonup 0 x\n\
+        5 keydown Space\n\
+        6 keydown Return";
+    let options = Options {
+        script: Some(Script::parse(script).unwrap()),
+        virtual_controllers: 1,
+        user_mappings: Some(path.clone()),
+        ..Options::headless(8)
+    };
+
+    let lesson = lazy_foo::find(lazy_foo::LESSONS, "mapping_wizard").unwrap();
+    {
+        let _lock = common::sdl_lock();
+        (lesson.run)(&options).unwrap();
+    }
+
+    let db = MappingDb::load(&path).unwrap();
+    assert!(db.errors().is_empty());
+    let mapping = &db.mappings()[0];
+    assert_eq!(mapping.name, virtual_controller::NAME);
+    assert_eq!(mapping.platform, Platform::current());
+    assert_eq!(mapping.source(Target::Button(Button::A)), Some(Source::Button(0)));
+    assert_eq!(mapping.source(Target::Button(Button::B)), Some(Source::Button(2)));
+    assert_eq!(mapping.source(Target::Button(Button::X)), None);
+}
+
+#[test]
+fn user_mappings_are_loaded_with_the_others() {
+    let path = mappings_path("loaded");
+    let guid = Guid::parse("0300000000f000000100000000000000").unwrap();
+    let mut mapping = Mapping::new(guid, "Homemade Pad");
+    mapping.bindings.push((Target::Button(Button::A), Source::Button(1)));
+    mapping.platform = Platform::current();
+    MappingDb::append(&path, &mapping).unwrap();
+
+    let options = Options { user_mappings: Some(path), ..Options::headless(1) };
+    let _lock = common::sdl_lock();
+    let mut app = AppBuilder::new("SDL Tutorial").options(&options).build().unwrap();
+    app.mapped_game_controller().unwrap();
+
+    let platform = Platform::current().unwrap();
+    assert_eq!(app.mappings().unwrap().get(guid, platform), Some(&mapping));
+}
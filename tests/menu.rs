@@ -0,0 +1,92 @@
+mod common;
+
+use std::path::Path;
+use sdl2_learn::lazy_foo::app::Options;
+use sdl2_learn::lazy_foo::capture::{Capture, Image};
+use sdl2_learn::lazy_foo::menu;
+use sdl2_learn::lazy_foo::script::Script;
+
+/// Runs the menu headless with `script` and returns what it presented on `frame`.
+fn menu_after(name: &str, script: &str, frame: u32) -> Image {
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join(format!("menu-{}.png", name));
+    let options = Options {
+        script: Some(Script::parse(script).unwrap()),
+        capture: Some(Capture { frame, path: path.clone() }),
+        ..Options::headless(frame)
+    };
+
+    let _lock = common::sdl_lock();
+    menu::run(&options).unwrap();
+    Image::load(&path).unwrap()
+}
+
+#[test]
+fn enter_starts_the_selected_lesson() {
+    let script = "\
+        1 keydown Down
+        1 keydown Down
+        1 keydown Down
+        2 keydown Return";
+    let capture = menu_after("start", script, 3);
+
+    assert!(capture.compare(&common::asset("imgs/key_presses/press.bmp"), 0).matches());
+}
+
+#[test]
+fn escape_returns_to_the_menu() {
+    let script = "\
+        1 keydown Up
+        1 keydown Up
+        2 keydown Return
+        3 keydown Escape";
+    let capture = menu_after("escape", script, 4);
+
+    assert_eq!(capture.pixel(0, 0), Some([0x10, 0x10, 0x30, 0xFF]));
+}
+
+#[test]
+fn last_lesson_scrolls_into_view() {
+    let capture = menu_after("scroll", "1 keydown Up", 2);
+
+    // The selected row is drawn in yellow somewhere in the list...
+    let selected = (88..368).any(|y| (0..capture.width()).any(|x| capture.pixel(x, y) == Some([0xFF, 0xFF, 0x00, 0xFF])));
+    assert!(selected);
+    // ...and no row spills into the gap above the description.
+    for y in 360..390 {
+        for x in 0..capture.width() {
+            assert_eq!(capture.pixel(x, y), Some([0x10, 0x10, 0x30, 0xFF]), "({}, {})", x, y);
+        }
+    }
+}
+
+#[test]
+fn last_lesson_starts_from_the_menu() {
+    let script = "\
+        1 keydown Up
+        2 keydown Return";
+    let capture = menu_after("last", script, 3);
+
+    // The analog_sticks sprite, resting in the middle of the window.
+    assert_eq!(capture.pixel(320, 240), Some([0x40, 0xC0, 0xFF, 0xFF]));
+}
+
+#[test]
+fn surface_lessons_start_in_a_window_of_their_own() {
+    let script = "\
+        1 keydown Down
+        2 keydown Return";
+    let capture = menu_after("image_screen", script, 4);
+
+    assert!(capture.compare(&common::asset("imgs/image_screen/hello_world.bmp"), 0).matches());
+}
+
+#[test]
+fn escape_leaves_a_surface_lesson_for_the_menu() {
+    let script = "\
+        1 keydown Down
+        2 keydown Return
+        4 keydown Escape";
+    let capture = menu_after("surface_escape", script, 6);
+
+    assert_eq!(capture.pixel(0, 0), Some([0x10, 0x10, 0x30, 0xFF]));
+}
@@ -0,0 +1,87 @@
+mod common;
+
+use std::fs;
+use std::path::PathBuf;
+use sdl2::keyboard::Keycode;
+use sdl2_learn::lazy_foo::{self, input, menu, LessonFn};
+use sdl2_learn::lazy_foo::app::{AppBuilder, Options};
+use sdl2_learn::lazy_foo::input::{ActionMap, Binding};
+use sdl2_learn::lazy_foo::script::Script;
+
+/// Runs `run` with `script` on a fresh bindings file and returns what it saved.
+fn saved_by(run: LessonFn, name: &str, script: &str, frames: u32) -> Option<ActionMap> {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("rebinding").join(format!("{}.toml", name));
+    let _ = fs::remove_file(&path);
+
+    let options = Options {
+        script: Some(Script::parse(script).unwrap()),
+        bindings: Some(path.clone()),
+        ..Options::headless(frames)
+    };
+
+    let _lock = common::sdl_lock();
+    run(&options).unwrap();
+    path.is_file().then(|| ActionMap::load(&path).unwrap())
+}
+
+/// Runs the rebinding lesson with `script` on a fresh bindings file and returns what it saved.
+fn rebind(name: &str, script: &str, frames: u32) -> Option<ActionMap> {
+    saved_by(lazy_foo::find(lazy_foo::LESSONS, "rebinding").unwrap().run, name, script, frames)
+}
+
+#[test]
+fn pressed_key_is_bound_and_saved() {
+    let saved = rebind("bound", "1 keydown Return\n2 keydown Q", 3).unwrap();
+
+    assert!(saved.bindings(input::UP).contains(&Binding::Key(Keycode::Q)));
+    assert!(saved.bindings(input::UP).contains(&Binding::Key(Keycode::Up)));
+}
+
+#[test]
+fn conflicting_key_needs_a_second_press() {
+    assert!(rebind("conflict", "1 keydown Return\n2 keydown Space", 3).is_none());
+
+    let saved = rebind("moved", "1 keydown Return\n2 keydown Space\n3 keyup Space\n4 keydown Space", 5).unwrap();
+    assert!(saved.bindings(input::UP).contains(&Binding::Key(Keycode::Space)));
+    assert!(!saved.bindings(input::CONFIRM).contains(&Binding::Key(Keycode::Space)));
+}
+
+#[test]
+fn clear_and_reset_are_saved() {
+    let cleared = rebind("cleared", "1 keydown Down\n2 keydown Delete", 3).unwrap();
+    assert!(cleared.bindings(input::DOWN).is_empty());
+
+    let reset = rebind("reset", "1 keydown Down\n2 keydown Delete\n3 keydown R", 4).unwrap();
+    assert_eq!(reset.bindings(input::DOWN), ActionMap::standard().bindings(input::DOWN));
+}
+
+#[test]
+fn escape_cancels_capture_when_started_from_the_menu() {
+    // Up wraps around to the last lessons; the fourth from the end is this one.
+    let script = "\
+        1 keydown Up
+        1 keydown Up
+        1 keydown Up
+        1 keydown Up
+        2 keydown Return
+        3 keydown Return
+        4 keydown Escape
+        5 keydown Return
+        6 keydown Q";
+    let saved = saved_by(menu::run, "menu", script, 7).unwrap();
+
+    assert!(saved.bindings(input::UP).contains(&Binding::Key(Keycode::Q)));
+}
+
+#[test]
+fn broken_bindings_file_keeps_the_defaults() {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("rebinding").join("broken.toml");
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(&path, "up = [\"key:Up\"\n").unwrap();
+
+    let options = Options { bindings: Some(path), ..Options::headless(1) };
+    let _lock = common::sdl_lock();
+    let app = AppBuilder::new("SDL Tutorial").options(&options).build().unwrap();
+
+    assert_eq!(app.actions().bindings(input::UP), ActionMap::standard().bindings(input::UP));
+}
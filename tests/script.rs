@@ -0,0 +1,93 @@
+mod common;
+
+use std::path::PathBuf;
+use sdl2::event::Event;
+use sdl2::keyboard::{Keycode, Mod, Scancode};
+use sdl2_learn::lazy_foo::app::Options;
+use sdl2_learn::lazy_foo::capture::Image;
+use sdl2_learn::lazy_foo::script::{self, Recorder, Script, ScriptError};
+
+fn key_presses_after(script: &str, frame: u32) -> Image {
+    let options = Options {
+        script: Some(Script::parse(script).unwrap()),
+        ..Options::headless(frame)
+    };
+
+    common::capture_lesson("key_presses", frame, options)
+}
+
+#[test]
+fn pressing_left_shows_left_image() {
+    let capture = key_presses_after("1 keydown Left", 2);
+
+    assert!(capture.compare(&common::asset("imgs/key_presses/left.bmp"), 0).matches());
+}
+
+#[test]
+fn unmapped_key_reverts_to_press_image() {
+    let capture = key_presses_after("1 keydown Left\n2 keydown Space", 3);
+
+    assert!(capture.compare(&common::asset("imgs/key_presses/press.bmp"), 0).matches());
+}
+
+#[test]
+fn parse_errors_report_their_line() {
+    let error = Script::parse("# comment\n1 keydown Left\n2 jump").unwrap_err();
+
+    match error {
+        ScriptError::Parse { line, .. } => assert_eq!(line, 3),
+        ScriptError::Io(error) => panic!("unexpected I/O error: {}", error),
+    }
+}
+
+#[test]
+fn recorded_lines_parse_back_to_the_same_events() {
+    let recording = "\
+        1 keydown Left Shift
+        1 mousemotion 10 20 1 -1 0
+        2 mousebuttondown right 2 10 20
+        2 window resized 800 600
+        3 controllerbuttondown 0 dpup
+        3 controlleraxismotion 1 righttrigger 32767
+        4 joyhatmotion 0 0 9
+        5 quit";
+
+    let script = Script::parse(recording).unwrap();
+    let replayed: Vec<String> = script.events().iter()
+        .map(|scripted| format!("{} {}", scripted.frame, script::format_event(&scripted.event).unwrap()))
+        .collect();
+
+    let expected: Vec<&str> = recording.lines().map(str::trim).collect();
+    assert_eq!(replayed, expected);
+}
+
+#[test]
+fn hash_keys_replay_instead_of_starting_a_comment() {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("hash_keys.txt");
+    let key_down = |keycode, scancode| Event::KeyDown {
+        timestamp: 0,
+        window_id: 0,
+        keycode: Some(keycode),
+        scancode: Some(scancode),
+        keymod: Mod::NOMOD,
+        repeat: false,
+    };
+
+    let mut recorder = Recorder::create(&path).unwrap();
+    recorder.record(1, &key_down(Keycode::Hash, Scancode::Backslash)).unwrap();
+    recorder.record(2, &key_down(Keycode::KpHash, Scancode::KpHash)).unwrap();
+    recorder.flush().unwrap();
+    drop(recorder);
+
+    let script = Script::load(&path).unwrap();
+    let keycodes: Vec<_> = script.events().iter()
+        .map(|scripted| match scripted.event {
+            Event::KeyDown { keycode, .. } => keycode,
+            _ => None
+        })
+        .collect();
+    assert_eq!(keycodes, [Some(Keycode::Hash), Some(Keycode::KpHash)]);
+
+    let commented = Script::parse("# header\n    # indented\n1 keydown #").unwrap();
+    assert_eq!(commented.events().len(), 1);
+}
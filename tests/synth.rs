@@ -0,0 +1,121 @@
+use std::time::Duration;
+use sdl2_learn::lazy_foo::synth::{Envelope, Note, Oscillator, Synth, Waveform};
+
+const RATE: u32 = 44100;
+
+/// Holds the volume at 1.0 from the first sample on, so only the oscillator shows.
+fn flat() -> Envelope {
+    Envelope { attack: Duration::ZERO, decay: Duration::ZERO, sustain: 1.0, release: Duration::ZERO }
+}
+
+fn render(synth: &mut Synth, samples: usize) -> Vec<f32> {
+    let mut buffer = vec![0.0; samples];
+    synth.render(&mut buffer);
+    buffer
+}
+
+#[test]
+fn silent_without_notes() {
+    let mut synth = Synth::new(RATE);
+
+    assert!(render(&mut synth, 256).iter().all(|&sample| sample == 0.0));
+}
+
+#[test]
+fn sine_has_the_requested_period() {
+    // 441 Hz at 44100 Hz repeats every 100 samples.
+    let mut oscillator = Oscillator::new(Waveform::Sine, 441.0);
+    let samples: Vec<f32> = (0..200).map(|_| oscillator.sample(RATE)).collect();
+
+    assert!(samples[0].abs() < 1e-6);
+    assert!((samples[25] - 1.0).abs() < 1e-3);
+    assert!((samples[75] + 1.0).abs() < 1e-3);
+    assert!((samples[125] - 1.0).abs() < 1e-3);
+}
+
+#[test]
+fn square_and_saw_stay_in_range() {
+    let mut square = Oscillator::new(Waveform::Square, 441.0);
+    let squares: Vec<f32> = (0..100).map(|_| square.sample(RATE)).collect();
+    // Either side of the half period, away from rounding at the edge itself.
+    assert!(squares[..49].iter().all(|&sample| sample == 1.0));
+    assert!(squares[51..].iter().all(|&sample| sample == -1.0));
+
+    let mut saw = Oscillator::new(Waveform::Saw, 441.0);
+    let saws: Vec<f32> = (0..100).map(|_| saw.sample(RATE)).collect();
+    assert_eq!(saws[0], -1.0);
+    assert!(saws.windows(2).all(|pair| pair[1] > pair[0]));
+}
+
+#[test]
+fn noise_is_bounded_and_varies() {
+    let mut noise = Oscillator::new(Waveform::Noise, 0.0);
+    let samples: Vec<f32> = (0..1000).map(|_| noise.sample(RATE)).collect();
+
+    assert!(samples.iter().all(|sample| (-1.0..=1.0).contains(sample)));
+    assert!(samples.iter().any(|&sample| sample > 0.5));
+    assert!(samples.iter().any(|&sample| sample < -0.5));
+}
+
+#[test]
+fn envelope_attacks_decays_and_sustains() {
+    let envelope = Envelope {
+        attack: Duration::from_millis(10),
+        decay: Duration::from_millis(10),
+        sustain: 0.5,
+        release: Duration::from_millis(10),
+    };
+    let mut synth = Synth::new(RATE);
+    synth.note_on(1, Note::new(Waveform::Square, 441.0).gain(1.0).envelope(envelope));
+
+    // 10 ms is 441 samples.
+    render(&mut synth, 220);
+    assert!((synth.voices()[0].level() - 0.5).abs() < 0.01);
+
+    render(&mut synth, 221);
+    assert!((synth.voices()[0].level() - 1.0).abs() < 0.01);
+
+    render(&mut synth, 441);
+    assert!((synth.voices()[0].level() - 0.5).abs() < 0.01);
+
+    render(&mut synth, 1000);
+    assert_eq!(synth.voices()[0].level(), 0.5);
+}
+
+#[test]
+fn released_notes_fade_out_and_stop() {
+    let envelope = Envelope { release: Duration::from_millis(10), ..flat() };
+    let mut synth = Synth::new(RATE);
+    synth.note_on(1, Note::new(Waveform::Square, 441.0).gain(1.0).envelope(envelope));
+    render(&mut synth, 10);
+
+    synth.note_off(1);
+    assert!(!synth.voices()[0].is_held());
+
+    let fading = render(&mut synth, 441);
+    assert!(fading[0].abs() > 0.9);
+    assert!(fading[400].abs() < 0.1);
+    assert!(synth.voices().is_empty());
+    assert!(render(&mut synth, 64).iter().all(|&sample| sample == 0.0));
+}
+
+#[test]
+fn voices_are_mixed_and_clipped() {
+    let mut synth = Synth::new(RATE);
+    synth.note_on(1, Note::new(Waveform::Square, 441.0).gain(0.25).envelope(flat()));
+    synth.note_on(2, Note::new(Waveform::Square, 441.0).gain(0.25).envelope(flat()));
+    assert!((render(&mut synth, 1)[0] - 0.5).abs() < 1e-6);
+
+    synth.note_on(3, Note::new(Waveform::Square, 441.0).gain(1.0).envelope(flat()));
+    assert_eq!(render(&mut synth, 1)[0], 1.0);
+}
+
+#[test]
+fn note_on_restarts_the_same_key() {
+    let mut synth = Synth::new(RATE);
+    synth.note_on(1, Note::from_a4(Waveform::Sine, 0));
+    synth.note_on(1, Note::from_a4(Waveform::Sine, 12));
+
+    assert_eq!(synth.voices().len(), 1);
+    assert_eq!(synth.voices()[0].key(), 1);
+}
@@ -0,0 +1,105 @@
+use std::path::PathBuf;
+use sdl2::pixels::{Color, PixelFormatEnum};
+use sdl2::rect::Rect;
+use sdl2::surface::Surface;
+use sdl2_learn::lazy_foo::assets::Assets;
+use sdl2_learn::lazy_foo::capture::Image;
+use sdl2_learn::lazy_foo::text::{Align, Font, FontError, Metrics, TextStyle};
+
+fn grid() -> Metrics {
+    Metrics::grid(128, 48, 8, 8, ' ').unwrap()
+}
+
+fn repo_assets() -> Assets {
+    Assets::with_roots(vec![PathBuf::from(env!("CARGO_MANIFEST_DIR"))])
+}
+
+#[test]
+fn grid_cells_follow_each_other() {
+    let metrics = grid();
+
+    assert_eq!(metrics.glyphs.len(), 96);
+    assert_eq!(metrics.glyph('!').unwrap().source, Some(Rect::new(8, 0, 8, 8)));
+    assert_eq!(metrics.glyph('A').unwrap().source, Some(Rect::new(8, 16, 8, 8)));
+    assert_eq!(metrics.glyph('é'), metrics.glyph('?'));
+}
+
+#[test]
+fn alignment_moves_lines_around_the_anchor() {
+    let metrics = grid();
+    let style = TextStyle::default().scale(2);
+
+    assert_eq!(metrics.layout("Hello", 100, 10, &style).bounds, Rect::new(100, 10, 80, 16));
+    assert_eq!(metrics.layout("Hello", 100, 10, &style.align(Align::Center)).bounds, Rect::new(60, 10, 80, 16));
+    assert_eq!(metrics.layout("Hello", 100, 10, &style.align(Align::Right)).bounds, Rect::new(20, 10, 80, 16));
+}
+
+#[test]
+fn long_lines_wrap_between_words() {
+    let metrics = grid();
+    let style = TextStyle::default().wrap(8 * 11);
+
+    assert_eq!(metrics.lines("press a key to start", &style), vec!["press a key", "to start"]);
+    assert_eq!(metrics.lines("unbreakable-word", &style), vec!["unbreakable-word"]);
+    assert_eq!(metrics.lines("two\nlines", &TextStyle::default()), vec!["two", "lines"]);
+    assert_eq!(metrics.measure("press a key to start", &style), (88, 16));
+}
+
+#[test]
+fn bmfont_description_is_parsed() {
+    let description = std::fs::read_to_string(
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("imgs/fonts/font8x8.fnt")
+    ).unwrap();
+    let (metrics, page) = Metrics::parse_bmfont(&description).unwrap();
+
+    assert_eq!(page, "font8x8.png");
+    assert_eq!(metrics.line_height, 10);
+    assert_eq!(metrics.glyph('A').unwrap().source, Some(Rect::new(8, 16, 6, 8)));
+    assert_eq!(metrics.glyph(' ').unwrap().source, None);
+    assert_eq!(metrics.line_width("Hi"), 7 + 5);
+}
+
+#[test]
+fn bmfont_kerning_and_errors() {
+    let description = "\
+        common lineHeight=12 pages=1
+        page id=0 file=\"my font.png\"
+        char id=65 x=0 y=0 width=6 height=8 xoffset=0 yoffset=2 xadvance=7
+        char id=86 x=8 y=0 width=6 height=8 xoffset=0 yoffset=2 xadvance=7
+        kerning first=65 second=86 amount=-2";
+    let (metrics, page) = Metrics::parse_bmfont(description).unwrap();
+    assert_eq!(page, "my font.png");
+    assert_eq!(metrics.line_width("AV"), 12);
+
+    match Metrics::parse_bmfont("common lineHeight=12 pages=1\nchar id=65 x=zero") {
+        Err(FontError::Parse { line, .. }) => assert_eq!(line, 2),
+        other => panic!("expected a parse error, got {:?}", other.map(|(_, page)| page)),
+    }
+}
+
+#[test]
+fn text_is_tinted_onto_surfaces() {
+    let mut font = Font::builtin().unwrap();
+    let mut surface = Surface::new(64, 16, PixelFormatEnum::RGBA32).unwrap();
+    surface.fill_rect(None, Color::BLACK).unwrap();
+
+    let bounds = font.draw_to_surface(&mut surface, "##", 0, 0, &TextStyle::new(Color::RED)).unwrap();
+    assert_eq!(bounds, Rect::new(0, 0, 16, 8));
+
+    // Row 2 of '#' is fully set from column 0 to 6.
+    let image = Image::from_surface(&surface).unwrap();
+    assert_eq!(image.pixel(3, 2), Some([0xFF, 0x00, 0x00, 0xFF]));
+    assert_eq!(image.pixel(7, 2), Some([0x00, 0x00, 0x00, 0xFF]));
+    assert_eq!(image.pixel(20, 12), Some([0x00, 0x00, 0x00, 0xFF]));
+}
+
+#[test]
+fn fonts_load_from_assets() {
+    let mut assets = repo_assets();
+
+    let bmfont = Font::load_bmfont(&mut assets, "imgs/fonts/font8x8.fnt").unwrap();
+    assert_eq!(bmfont.measure("Hi", &TextStyle::default()), (12, 10));
+
+    let grid = Font::load_grid(&mut assets, "imgs/fonts/font8x8.png", 8, 8, ' ').unwrap();
+    assert_eq!(grid.measure("Hi", &TextStyle::default()), (16, 8));
+}
@@ -0,0 +1,142 @@
+#![cfg(feature = "ttf")]
+
+mod common;
+
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2_learn::lazy_foo::app::{App, AppBuilder, Options};
+use sdl2_learn::lazy_foo::assets::{Assets, TextureCache};
+use sdl2_learn::lazy_foo::capture::Image;
+use sdl2_learn::lazy_foo::ttf::{FontFamily, Fonts, RenderMode, TextCache, TrueType};
+
+const FONT_PATH: &str = "imgs/true_type_fonts/DejaVuSansMono.ttf";
+const BACKGROUND: Color = Color::RGB(0xFF, 0xFF, 0xC0);
+
+fn headless_app() -> App {
+    AppBuilder::new("SDL Tutorial").options(&Options::headless(1)).accelerated().build().unwrap()
+}
+
+fn assets() -> Assets {
+    Assets::new(Some(Path::new(env!("CARGO_MANIFEST_DIR"))))
+}
+
+#[test]
+fn each_size_is_opened_once() {
+    let _lock = common::sdl_lock();
+    let ttf = TrueType::init().unwrap();
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("ttf");
+    fs::create_dir_all(&dir).unwrap();
+    fs::copy(Path::new(env!("CARGO_MANIFEST_DIR")).join(FONT_PATH), dir.join("copy.ttf")).unwrap();
+    let assets = Assets::with_roots(vec![dir.clone()]);
+
+    let mut family = FontFamily::new(&ttf, "copy.ttf");
+    let height = family.font(&assets, 16).unwrap().height();
+    fs::remove_file(dir.join("copy.ttf")).unwrap();
+
+    // The file is gone: only the size opened before can still be used.
+    assert_eq!(family.font(&assets, 16).unwrap().height(), height);
+    assert!(family.font(&assets, 24).is_err());
+}
+
+#[test]
+fn glyph_metrics_grow_with_the_size() {
+    let _lock = common::sdl_lock();
+    let ttf = TrueType::init().unwrap();
+    let assets = assets();
+    let mut family = FontFamily::new(&ttf, FONT_PATH);
+
+    let small = family.glyph_metrics(&assets, 16, 'g').unwrap().unwrap();
+    let large = family.glyph_metrics(&assets, 32, 'g').unwrap().unwrap();
+    assert!(small.advance > 0);
+    assert!(large.advance > small.advance);
+    // 'g' hangs below the baseline.
+    assert!(small.miny < 0);
+
+    // Every glyph of a monospaced font advances as far.
+    assert_eq!(family.glyph_metrics(&assets, 16, 'W').unwrap().unwrap().advance, small.advance);
+}
+
+#[test]
+fn rendered_text_is_kept_per_key() {
+    let _lock = common::sdl_lock();
+    let mut app = headless_app();
+    let ttf = app.ttf().unwrap();
+    let texture_creator = app.texture_creator();
+    let mut textures = TextureCache::new(&texture_creator);
+    let mut text = TextCache::new(FontFamily::new(&ttf, FONT_PATH));
+    let assets = assets();
+
+    let first = text.text(&assets, &mut textures, "Hello", 16, RenderMode::Blended, Color::BLACK).unwrap().raw();
+    assert_eq!(text.text(&assets, &mut textures, "Hello", 16, RenderMode::Blended, Color::BLACK).unwrap().raw(), first);
+    assert_ne!(text.text(&assets, &mut textures, "Hello", 24, RenderMode::Blended, Color::BLACK).unwrap().raw(), first);
+    assert_ne!(text.text(&assets, &mut textures, "Hello", 16, RenderMode::Solid, Color::BLACK).unwrap().raw(), first);
+    assert_ne!(text.text(&assets, &mut textures, "Hello", 16, RenderMode::Blended, Color::RED).unwrap().raw(), first);
+    assert_ne!(text.text(&assets, &mut textures, "World", 16, RenderMode::Blended, Color::BLACK).unwrap().raw(), first);
+}
+
+#[test]
+fn fonts_keep_one_text_cache_per_file() {
+    let _lock = common::sdl_lock();
+    let mut app = headless_app();
+    let ttf = app.ttf().unwrap();
+    let texture_creator = app.texture_creator();
+    let mut textures = TextureCache::new(&texture_creator);
+    let mut fonts = Fonts::new(&ttf);
+    let assets = assets();
+
+    let first = fonts.cache(FONT_PATH).text(&assets, &mut textures, "Hello", 16, RenderMode::Blended, Color::BLACK)
+        .unwrap().raw();
+    let again = fonts.cache(FONT_PATH).text(&assets, &mut textures, "Hello", 16, RenderMode::Blended, Color::BLACK)
+        .unwrap().raw();
+    assert_eq!(again, first);
+}
+
+#[test]
+fn only_shaded_text_covers_its_background() {
+    let _lock = common::sdl_lock();
+    let mut app = headless_app();
+    let ttf = app.ttf().unwrap();
+    let texture_creator = app.texture_creator();
+    let mut textures = TextureCache::new(&texture_creator);
+    let mut text = TextCache::new(FontFamily::new(&ttf, FONT_PATH));
+    let assets = assets();
+
+    let modes = [
+        (RenderMode::Solid, Color::BLUE),
+        (RenderMode::Shaded { background: BACKGROUND }, BACKGROUND),
+        (RenderMode::Blended, Color::BLUE),
+    ];
+    for (mode, corner) in modes {
+        let texture = text.text(&assets, &mut textures, "Hello", 16, mode, Color::BLACK).unwrap();
+        let query = texture.query();
+        assert!(query.width > 0 && query.height > 0, "{:?}", mode);
+
+        let canvas = app.canvas();
+        canvas.set_draw_color(Color::BLUE);
+        canvas.clear();
+        canvas.copy(texture, None, Rect::new(0, 0, query.width, query.height)).unwrap();
+        let image = Image::from_canvas(canvas).unwrap();
+
+        let Color { r, g, b, .. } = corner;
+        assert_eq!(image.pixel(0, 0), Some([r, g, b, 0xFF]), "{:?}", mode);
+    }
+}
+
+#[test]
+fn the_app_owns_sdl_ttf() {
+    let _lock = common::sdl_lock();
+    let mut app = headless_app();
+
+    let ttf = app.ttf().unwrap();
+    assert!(Rc::ptr_eq(&app.ttf().unwrap(), &ttf));
+    // SDL_ttf is already initialized while the app lives...
+    assert!(TrueType::init().is_err());
+
+    drop(ttf);
+    drop(app);
+    // ...and quit with it.
+    assert!(TrueType::init().is_ok());
+}